@@ -1,5 +1,6 @@
 use anyhow::Result;
 use quickjs_wasm_rs::{Deserializer, JSContextRef, JSValueRef, Serializer};
+use serde_json::{Number, Value};
 
 /// Transcodes a byte slice containing a JSON encoded payload into a [`JSValueRef`].
 ///
@@ -22,3 +23,95 @@ pub fn transcode_output(val: JSValueRef) -> Result<Vec<u8>> {
     serde_transcode::transcode(&mut deserializer, &mut serializer)?;
     Ok(output)
 }
+
+/// Converts a [`serde_json::Value`] into a [`JSValueRef`] by creating a
+/// JavaScript value for each JSON node directly, without going through the
+/// generic serializer.
+///
+/// Integers outside of JavaScript's safe integer range are converted to
+/// `BigInt`s, other numbers to JavaScript numbers.
+///
+/// Arguments:
+/// * `context` - A reference to the [`JSContextRef`] that will contain the
+///   returned [`JSValueRef`].
+/// * `value` - The JSON value to convert.
+pub fn from_json_value<'a>(context: &'a JSContextRef, value: &Value) -> Result<JSValueRef<'a>> {
+    match value {
+        Value::Null => context.null_value(),
+        Value::Bool(flag) => context.value_from_bool(*flag),
+        Value::Number(number) => from_json_number(context, number),
+        Value::String(string) => context.value_from_str(string),
+        Value::Array(items) => {
+            let array = context.array_value()?;
+            for item in items {
+                array.append_property(from_json_value(context, item)?)?;
+            }
+            Ok(array)
+        }
+        Value::Object(entries) => {
+            let object = context.object_value()?;
+            for (key, value) in entries {
+                object.set_property(key.as_str(), from_json_value(context, value)?)?;
+            }
+            Ok(object)
+        }
+    }
+}
+
+fn from_json_number<'a>(context: &'a JSContextRef, number: &Number) -> Result<JSValueRef<'a>> {
+    if let Some(v) = number.as_i64() {
+        context.value_from_i64(v)
+    } else if let Some(v) = number.as_u64() {
+        context.value_from_u64(v)
+    } else {
+        context.value_from_f64(number.as_f64().unwrap_or(f64::NAN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::from_json_value;
+    use anyhow::Result;
+    use quickjs_wasm_rs::JSContextRef;
+    use serde_json::json;
+
+    #[test]
+    fn test_nested_objects() -> Result<()> {
+        let context = JSContextRef::default();
+        let value = json!({
+            "name": "javy",
+            "tags": ["wasm", null, true],
+            "nested": { "depth": 2, "ratio": 0.5, "empty": {} }
+        });
+        context
+            .global_object()?
+            .set_property("value", from_json_value(&context, &value)?)?;
+        let output = context.eval_global("main", "JSON.stringify(value)")?;
+        assert_eq!(
+            value,
+            serde_json::from_str::<serde_json::Value>(output.as_str()?)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_large_integers() -> Result<()> {
+        let context = JSContextRef::default();
+        let value = json!([
+            9007199254740991_i64,
+            9223372036854775808_u64,
+            -9007199254740993_i64
+        ]);
+        context
+            .global_object()?
+            .set_property("value", from_json_value(&context, &value)?)?;
+        let matches = context.eval_global(
+            "main",
+            "value[0] === 9007199254740991 \
+             && value[1] === 9223372036854775808n \
+             && value[2] === -9007199254740993n",
+        )?;
+        assert!(matches.as_bool()?);
+        Ok(())
+    }
+}
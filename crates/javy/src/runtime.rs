@@ -49,6 +49,17 @@ impl Runtime {
     pub fn context(&self) -> &JSContextRef {
         &self.context
     }
+
+    /// Creates a new [`JSContextRef`] in this runtime, isolated from
+    /// [`Self::context()`] and every other context created with this method.
+    ///
+    /// Each context has its own global object and built-ins, so separate
+    /// guests can be evaluated in the same runtime without seeing each
+    /// other's globals. Values can't be passed between contexts directly,
+    /// see [`JSContextRef::new_realm`] for details.
+    pub fn new_context(&self) -> Result<JSContextRef> {
+        self.context.new_realm()
+    }
 }
 
 impl Default for Runtime {
@@ -58,3 +69,26 @@ impl Default for Runtime {
         Self::new(Config::default()).unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Runtime;
+    use anyhow::Result;
+
+    #[test]
+    fn test_new_context_is_isolated() -> Result<()> {
+        let runtime = Runtime::default();
+        let tenant_a = runtime.new_context()?;
+        let tenant_b = runtime.new_context()?;
+
+        tenant_a.eval_global("a.js", "globalThis.tenant = 'a';")?;
+        tenant_b.eval_global("b.js", "globalThis.tenant = 'b';")?;
+        assert_eq!("a", tenant_a.eval_global("a.js", "tenant")?.as_str()?);
+        assert_eq!("b", tenant_b.eval_global("b.js", "tenant")?.as_str()?);
+        assert!(runtime
+            .context()
+            .eval_global("main.js", "globalThis.tenant")?
+            .is_undefined());
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use quickjs_wasm_rs::{Deserializer, JSContextRef};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Record {
+    id: u32,
+    first_name: String,
+    last_name: String,
+    email: String,
+    country: String,
+    active: bool,
+    score: f64,
+    visits: u32,
+}
+
+const RECORDS: &str = "Array.from({length: 50000}, (_, i) => ({
+    id: i,
+    first_name: 'Ada',
+    last_name: 'Lovelace',
+    email: 'ada@example.com',
+    country: 'GB',
+    active: i % 2 === 0,
+    score: i / 3,
+    visits: i % 100,
+}))";
+
+fn deserialize_rows(c: &mut Criterion) {
+    let context = JSContextRef::default();
+    let records = context.eval_global("records.js", RECORDS).unwrap();
+
+    let mut group = c.benchmark_group("deserialize_rows");
+    group.sample_size(10);
+    group.bench_function("without key cache", |b| {
+        b.iter(|| Vec::<Record>::deserialize(&mut Deserializer::from(records)).unwrap())
+    });
+    group.bench_function("with key cache", |b| {
+        b.iter(|| {
+            Deserializer::from(records)
+                .deserialize_rows::<Record>()
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, deserialize_rows);
+criterion_main!(benches);
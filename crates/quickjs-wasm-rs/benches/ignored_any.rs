@@ -0,0 +1,41 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use quickjs_wasm_rs::{Deserializer, JSContextRef};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Summary {
+    id: u32,
+    name: String,
+}
+
+/// An object with a couple of fields the target struct reads and many large ones it ignores.
+const WIDE: &str = "({
+    id: 1,
+    name: 'wide',
+    ...Object.fromEntries(Array.from({length: 200}, (_, i) => [
+        'field' + i,
+        {label: 'x'.repeat(100), values: Array.from({length: 50}, (_, j) => j)},
+    ])),
+})";
+
+fn ignored_any(c: &mut Criterion) {
+    let context = JSContextRef::default();
+    let wide = context.eval_global("wide.js", WIDE).unwrap();
+
+    let mut group = c.benchmark_group("ignored_any");
+    group.bench_function("narrow struct", |b| {
+        b.iter(|| Summary::deserialize(&mut Deserializer::from(wide)).unwrap())
+    });
+    group.bench_function("whole object", |b| {
+        b.iter(|| {
+            HashMap::<String, serde_json::Value>::deserialize(&mut Deserializer::from(wide))
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, ignored_any);
+criterion_main!(benches);
@@ -0,0 +1,79 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use quickjs_wasm_rs::{Deserializer, JSContextRef, JSValueRef};
+use serde::Deserialize;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts allocations so the benchmark can report how many `intern_keys` saves.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+const RECORDS: &str = "Array.from({length: 10000}, (_, i) => ({
+    identifier: i,
+    category: i % 7,
+    quantity: i % 13,
+    warehouse: i % 3,
+    'métrique': i % 5,
+}))";
+
+type Rows = Vec<HashMap<String, u32>>;
+
+fn deserialize(records: JSValueRef, intern_keys: bool) -> Rows {
+    let mut deserializer = Deserializer::from(records);
+    deserializer.intern_keys(intern_keys);
+    Rows::deserialize(&mut deserializer).unwrap()
+}
+
+fn count_allocations(records: JSValueRef, intern_keys: bool) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let rows = deserialize(records, intern_keys);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    drop(rows);
+    allocations
+}
+
+fn intern_keys(c: &mut Criterion) {
+    let context = JSContextRef::default();
+    let records = context.eval_global("records.js", RECORDS).unwrap();
+
+    let before = context.memory_usage().malloc_count;
+    deserialize(records, false);
+    let js_without = context.memory_usage().malloc_count - before;
+    let before = context.memory_usage().malloc_count;
+    deserialize(records, true);
+    let js_with = context.memory_usage().malloc_count - before;
+    let rust_without = count_allocations(records, false);
+    let rust_with = count_allocations(records, true);
+    println!("allocations without interning: {rust_without} Rust, {js_without} QuickJS");
+    println!("allocations with interning: {rust_with} Rust, {js_with} QuickJS");
+    // Only filling the key cache allocates, cached keys are passed on without copying them.
+    assert!(
+        rust_with <= rust_without + 16,
+        "interning allocated for every key: {rust_with} allocations, {rust_without} without"
+    );
+
+    let mut group = c.benchmark_group("intern_keys");
+    group.sample_size(10);
+    group.bench_function("disabled", |b| b.iter(|| deserialize(records, false)));
+    group.bench_function("enabled", |b| b.iter(|| deserialize(records, true)));
+    group.finish();
+}
+
+criterion_group!(benches, intern_keys);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use quickjs_wasm_rs::{JSContextRef, Serializer};
+use serde::Serialize;
+
+fn serialize_seq(c: &mut Criterion) {
+    let context = JSContextRef::default();
+    let mut group = c.benchmark_group("serialize_seq");
+    for count in [100, 1_000] {
+        let numbers: Vec<u32> = (0..count).collect();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(count),
+            &numbers,
+            |b, numbers| {
+                b.iter(|| {
+                    let mut serializer = Serializer::from_context(&context).unwrap();
+                    numbers.serialize(&mut serializer).unwrap();
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, serialize_seq);
+criterion_main!(benches);
@@ -1,34 +1,85 @@
 use super::constants::{MAX_SAFE_INTEGER, MIN_SAFE_INTEGER};
 use super::error::JSError;
 use super::exception::Exception;
+use super::memory::MemoryStats;
 use super::value::JSValueRef;
 use crate::js_value::{self, qjs_convert};
-use anyhow::{anyhow, Result};
+use crate::serialize::de::Deserializer;
+use anyhow::{anyhow, bail, Context as _, Result};
 use once_cell::sync::Lazy;
 use quickjs_wasm_sys::{
-    ext_js_null, ext_js_undefined, JSCFunctionData, JSClassDef, JSClassID, JSContext, JSValue,
-    JS_Eval, JS_ExecutePendingJob, JS_GetGlobalObject, JS_GetOpaque, JS_GetRuntime,
-    JS_IsJobPending, JS_NewArray, JS_NewArrayBufferCopy, JS_NewBigInt64, JS_NewBool_Ext,
-    JS_NewCFunctionData, JS_NewClass, JS_NewClassID, JS_NewContext, JS_NewFloat64_Ext,
-    JS_NewInt32_Ext, JS_NewInt64_Ext, JS_NewObject, JS_NewObjectClass, JS_NewRuntime,
-    JS_NewStringLen, JS_NewUint32_Ext, JS_ReadObject, JS_SetOpaque, JS_ThrowInternalError,
+    ext_js_null, ext_js_undefined, JSCFunctionData, JSClassDef, JSClassID, JSContext,
+    JSHostPromiseRejectionTracker, JSMemoryUsage, JSModuleDef, JSRuntime, JSValue,
+    JS_AddIntrinsicBaseObjects, JS_AddIntrinsicBigInt, JS_AddIntrinsicDate, JS_AddIntrinsicEval,
+    JS_AddIntrinsicJSON, JS_AddIntrinsicMapSet, JS_AddIntrinsicPromise, JS_AddIntrinsicProxy,
+    JS_AddIntrinsicRegExp, JS_AddIntrinsicStringNormalize, JS_AddIntrinsicTypedArrays,
+    JS_CallConstructor, JS_ComputeMemoryUsage, JS_DupValue_Ext, JS_Eval, JS_ExecutePendingJob,
+    JS_FreeContext, JS_FreeValue_Ext, JS_GetContextOpaque, JS_GetGlobalObject,
+    JS_GetHostPromiseRejectionTracker_Ext, JS_GetInterruptHandler_Ext, JS_GetModuleDef_Ext,
+    JS_GetOpaque, JS_GetRuntime, JS_IsJobPending, JS_NewArray, JS_NewArrayBufferCopy,
+    JS_NewBigInt64, JS_NewBool_Ext, JS_NewCFunctionData, JS_NewClass, JS_NewClassID, JS_NewContext,
+    JS_NewContextRaw, JS_NewFloat64_Ext, JS_NewInt32_Ext, JS_NewInt64_Ext, JS_NewObject,
+    JS_NewObjectClass, JS_NewRuntime, JS_NewStringLen, JS_NewUint32_Ext, JS_ParseJSON,
+    JS_ReadObject, JS_RunGC, JS_SetContextOpaque, JS_SetHostPromiseRejectionTracker,
+    JS_SetInterruptHandler, JS_SetModuleLoaderFunc, JS_SetOpaque, JS_Throw, JS_ThrowInternalError,
     JS_ThrowRangeError, JS_ThrowReferenceError, JS_ThrowSyntaxError, JS_ThrowTypeError,
-    JS_WriteObject, JS_EVAL_FLAG_COMPILE_ONLY, JS_EVAL_TYPE_GLOBAL, JS_EVAL_TYPE_MODULE,
-    JS_READ_OBJ_BYTECODE, JS_WRITE_OBJ_BYTECODE,
+    JS_WriteObject, JS_EVAL_FLAG_COMPILE_ONLY, JS_EVAL_FLAG_STRICT, JS_EVAL_TYPE_GLOBAL,
+    JS_EVAL_TYPE_MODULE, JS_READ_OBJ_BYTECODE, JS_WRITE_OBJ_BYTECODE,
 };
+use serde::de::DeserializeOwned;
 use std::any::TypeId;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::convert::TryInto;
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
+use std::io::{self, BufRead, Read};
+use std::mem::MaybeUninit;
 use std::os::raw::{c_char, c_int, c_void};
 use std::ptr;
+use std::rc::Rc;
 use std::str;
 use std::sync::Mutex;
 
+/// Size of the chunks read by [`JSContextRef::parse_json_reader`].
+const JSON_READ_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Number of jobs [`JSContextRef::eval_async`] runs before yielding to the executor.
+#[cfg(feature = "tokio")]
+const JOBS_PER_BATCH: usize = 64;
+
 pub(super) static CLASSES: Lazy<Mutex<HashMap<TypeId, JSClassID>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+/// Settings of a single context, kept behind its opaque pointer so evaluating code can read them
+/// without locking anything.
+#[derive(Default)]
+struct ContextSettings {
+    /// Set by [`JSContextRef::force_strict`].
+    strict: Cell<bool>,
+}
+
+/// Loader set by [`JSContextRef::set_module_loader`].
+type ModuleLoader = Box<dyn Fn(&str) -> Result<String>>;
+
+/// Addresses of the boxed `ModuleLoader` set for each runtime, keyed by the address of the runtime,
+/// so they can be freed once they're replaced.
+static MODULE_LOADERS: Lazy<Mutex<HashMap<usize, usize>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The `require` loader and the modules loaded so far by [`JSContextRef::eval_commonjs`].
+struct CommonJsModules {
+    loader: ModuleLoader,
+    /// The `module` object of each loaded module, by name.
+    cache: RefCell<HashMap<String, JSValue>>,
+}
+
+/// Rejected promises along with their formatted rejection reason.
+type Rejections = Vec<(JSValue, String)>;
+
+/// Unhandled promise rejections, keyed by the address of the runtime they happened in.
+static UNHANDLED_REJECTIONS: Lazy<Mutex<HashMap<usize, Rejections>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
 /// `JSContextRef` is a wrapper around a raw pointer to a QuickJS `JSContext`.
 ///
 /// This struct provides a safe interface for interacting with the underlying
@@ -70,7 +121,200 @@ impl Default for JSContextRef {
     }
 }
 
+/// Selects the groups of built-in objects added to a context created with
+/// [`JSContextRef::with_intrinsics`].
+///
+/// Everything is enabled by default, which matches [`JSContextRef::default`]. The base objects
+/// (`Object`, `Function`, `Array`, `Error`, etc.) are always added since QuickJS depends on them.
+///
+/// # Example
+///
+/// ```
+/// let mut intrinsics = Intrinsics::default();
+/// intrinsics.date(false).eval(false);
+/// let context = JSContextRef::with_intrinsics(&intrinsics)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Intrinsics {
+    date: bool,
+    eval: bool,
+    string_normalize: bool,
+    regexp: bool,
+    json: bool,
+    proxy: bool,
+    map_set: bool,
+    typed_arrays: bool,
+    promise: bool,
+    big_int: bool,
+}
+
+impl Default for Intrinsics {
+    fn default() -> Self {
+        Self {
+            date: true,
+            eval: true,
+            string_normalize: true,
+            regexp: true,
+            json: true,
+            proxy: true,
+            map_set: true,
+            typed_arrays: true,
+            promise: true,
+            big_int: true,
+        }
+    }
+}
+
+impl Intrinsics {
+    /// Adds `Date`.
+    pub fn date(&mut self, enabled: bool) -> &mut Self {
+        self.date = enabled;
+        self
+    }
+
+    /// Adds support for compiling source code, which includes `eval` in JavaScript.
+    ///
+    /// Without it [`JSContextRef::eval_global`] and the other methods taking source code fail, and
+    /// only bytecode can be run with [`JSContextRef::eval_binary`].
+    pub fn eval(&mut self, enabled: bool) -> &mut Self {
+        self.eval = enabled;
+        self
+    }
+
+    /// Adds `String.prototype.normalize`.
+    pub fn string_normalize(&mut self, enabled: bool) -> &mut Self {
+        self.string_normalize = enabled;
+        self
+    }
+
+    /// Adds `RegExp` and the regular expression compiler.
+    pub fn regexp(&mut self, enabled: bool) -> &mut Self {
+        self.regexp = enabled;
+        self
+    }
+
+    /// Adds `JSON`.
+    pub fn json(&mut self, enabled: bool) -> &mut Self {
+        self.json = enabled;
+        self
+    }
+
+    /// Adds `Proxy` and `Reflect`.
+    pub fn proxy(&mut self, enabled: bool) -> &mut Self {
+        self.proxy = enabled;
+        self
+    }
+
+    /// Adds `Map`, `Set`, `WeakMap` and `WeakSet`.
+    pub fn map_set(&mut self, enabled: bool) -> &mut Self {
+        self.map_set = enabled;
+        self
+    }
+
+    /// Adds `ArrayBuffer`, the typed arrays and `DataView`.
+    pub fn typed_arrays(&mut self, enabled: bool) -> &mut Self {
+        self.typed_arrays = enabled;
+        self
+    }
+
+    /// Adds `Promise`.
+    pub fn promise(&mut self, enabled: bool) -> &mut Self {
+        self.promise = enabled;
+        self
+    }
+
+    /// Adds `BigInt`.
+    ///
+    /// Without it, `u64` and `i64` values outside of the safe integer range can't be converted to
+    /// JavaScript values.
+    pub fn big_int(&mut self, enabled: bool) -> &mut Self {
+        self.big_int = enabled;
+        self
+    }
+}
+
 impl JSContextRef {
+    /// Creates a context in a new runtime with only the selected built-in objects.
+    ///
+    /// Leaving out unused intrinsics reduces both the attack surface and the memory used by the
+    /// context.
+    pub fn with_intrinsics(intrinsics: &Intrinsics) -> Result<Self> {
+        let runtime = unsafe { JS_NewRuntime() };
+        if runtime.is_null() {
+            bail!("Couldn't create JavaScript runtime");
+        }
+
+        let inner = unsafe { JS_NewContextRaw(runtime) };
+        if inner.is_null() {
+            bail!("Couldn't create JavaScript context");
+        }
+
+        unsafe {
+            JS_AddIntrinsicBaseObjects(inner);
+            if intrinsics.date {
+                JS_AddIntrinsicDate(inner);
+            }
+            if intrinsics.eval {
+                JS_AddIntrinsicEval(inner);
+            }
+            if intrinsics.string_normalize {
+                JS_AddIntrinsicStringNormalize(inner);
+            }
+            if intrinsics.regexp {
+                JS_AddIntrinsicRegExp(inner);
+            }
+            if intrinsics.json {
+                JS_AddIntrinsicJSON(inner);
+            }
+            if intrinsics.proxy {
+                JS_AddIntrinsicProxy(inner);
+            }
+            if intrinsics.map_set {
+                JS_AddIntrinsicMapSet(inner);
+            }
+            if intrinsics.typed_arrays {
+                JS_AddIntrinsicTypedArrays(inner);
+            }
+            if intrinsics.promise {
+                JS_AddIntrinsicPromise(inner);
+            }
+            if intrinsics.big_int {
+                JS_AddIntrinsicBigInt(inner);
+            }
+        }
+
+        Ok(Self { inner })
+    }
+
+    /// Creates a new context, or realm, in the same runtime as this one.
+    ///
+    /// The new context has its own global object and built-ins, so globals and changes to
+    /// prototypes made in one context aren't visible in the other, while the runtime's allocator,
+    /// atoms and class registrations are shared, which takes less memory than a runtime per
+    /// context. Settings that belong to the runtime, like the module loader, the interrupt
+    /// handler, memory limits and the queue of pending jobs, are shared as well.
+    ///
+    /// Values belong to the context that created them and shouldn't be passed to another one,
+    /// since an object would give the other context access to its prototypes. Copy values across
+    /// instead, for example with [`from_qjs_value`](crate::from_qjs_value) and
+    /// [`to_qjs_value`](crate::to_qjs_value).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let first = JSContextRef::default();
+    /// let second = first.new_realm()?;
+    /// first.eval_global("main.js", "globalThis.secret = 1;")?;
+    /// assert!(second.eval_global("main.js", "globalThis.secret")?.is_undefined());
+    /// ```
+    pub fn new_realm(&self) -> Result<Self> {
+        let inner = unsafe { JS_NewContext(JS_GetRuntime(self.inner)) };
+        if inner.is_null() {
+            bail!("Couldn't create JavaScript context");
+        }
+        Ok(Self { inner })
+    }
+
     /// Evaluates JavaScript code in the global scope.
     ///
     /// This method takes JavaScript code as a string and evaluates it in the global scope of the
@@ -81,16 +325,362 @@ impl JSContextRef {
     /// * `name`: A string representing the name of the script.
     /// * `contents`: The JavaScript code to be evaluated as a string.
     ///
+    /// # Completion value
+    ///
+    /// The returned value is the script's completion value, like in a REPL: the value of the last
+    /// expression statement that was evaluated, including ones nested in blocks, `if` statements
+    /// and loops. Declarations don't produce a value, so a script ending with `var x = 1;` returns
+    /// `undefined` even though `x` is set. End the script with the expression, as in
+    /// `var x = 1; x`, to get it back without reading it from the global object. No wrapping in
+    /// parentheses or `return` is needed.
+    ///
     /// # Example
     ///
     /// ```
     /// let context = JSContextRef::default();
-    /// context.eval_global("test.js", "1 + 1")?;
+    /// let sum = context.eval_global("test.js", "1 + 1")?;
+    /// assert_eq!(2, sum.as_i32_unchecked());
     /// ```
     pub fn eval_global(&self, name: &str, contents: &str) -> Result<JSValueRef> {
         self.eval(name, contents, EvalType::Global, false)
     }
 
+    /// Evaluates JavaScript code in the global scope with `args` available as a global
+    /// `arguments` array, returning the completion value like [`Self::eval_global`].
+    ///
+    /// Once the script has been evaluated, also when it fails, the `arguments` global is put back
+    /// to the value it had before, or removed if there wasn't one. Functions have their own
+    /// `arguments`, so the array is only visible at the top level of the script.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// let args = [context.value_from_i32(2)?, context.value_from_i32(3)?];
+    /// let sum = context.eval_with_args("sum.js", "arguments[0] + arguments[1]", &args)?;
+    /// ```
+    pub fn eval_with_args(
+        &self,
+        name: &str,
+        contents: &str,
+        args: &[JSValueRef],
+    ) -> Result<JSValueRef> {
+        let global = self.global_object()?;
+        let array = global.get_property("Array")?;
+        let arguments = array.get_property("of")?.call(&array, args)?;
+        let previous = if global.has_own_property("arguments")? {
+            Some(global.get_property("arguments")?)
+        } else {
+            None
+        };
+        global.set_property("arguments", arguments)?;
+
+        let result = self.eval_global(name, contents);
+        match previous {
+            Some(previous) => global.set_property("arguments", previous)?,
+            None => {
+                global.delete_property("arguments")?;
+            }
+        }
+        result
+    }
+
+    /// Evaluates setup code, like a library of helper functions, in the global scope.
+    ///
+    /// Functions and variables declared by the prelude stay defined for the lifetime of the
+    /// context, so later evaluations can use them without evaluating the prelude again. Errors are
+    /// returned with context stating that they come from the prelude.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// context.load_prelude("function double(n) { return n * 2; }")?;
+    /// context.eval_global("main.js", "double(21)")?;
+    /// ```
+    pub fn load_prelude(&self, source: &str) -> Result<()> {
+        self.eval_global("prelude.js", source)
+            .context("Failed to load the prelude")?;
+        Ok(())
+    }
+
+    /// Evaluates and compiles all scripts in strict mode, as if they started with `'use strict'`.
+    ///
+    /// This turns silent failures, like assigning to an undeclared variable or to a read-only
+    /// property, into errors. Modules are always strict. Bytecode keeps the mode it was compiled
+    /// with, so this doesn't affect [`Self::eval_binary`] for bytecode compiled without it.
+    /// Disabled by default.
+    pub fn force_strict(&self, enabled: bool) {
+        match self.settings() {
+            Some(settings) => settings.strict.set(enabled),
+            None if enabled => {
+                let settings = ContextSettings {
+                    strict: Cell::new(true),
+                };
+                let settings = Box::into_raw(Box::new(settings));
+                unsafe { JS_SetContextOpaque(self.inner, settings as *mut c_void) };
+            }
+            None => {}
+        }
+    }
+
+    /// Returns the context's settings, if any were changed from their defaults.
+    fn settings(&self) -> Option<&ContextSettings> {
+        let settings = unsafe { JS_GetContextOpaque(self.inner) } as *const ContextSettings;
+        unsafe { settings.as_ref() }
+    }
+
+    /// Replaces the context with a new one in the same runtime, so nothing defined by previous
+    /// scripts is visible anymore, including changes to built-in prototypes.
+    ///
+    /// Settings of the context itself, like [`Self::force_strict`], are cleared. Settings of the
+    /// runtime, like the module loader, the interrupt handler and memory limits, are kept since
+    /// contexts created with [`Self::new_realm`] share them. Fails if jobs are pending, since they
+    /// would still run in the runtime.
+    pub(super) fn reset(&mut self) -> Result<()> {
+        if self.is_pending() {
+            bail!("Can't reset a context with pending jobs");
+        }
+
+        unsafe {
+            let settings = JS_GetContextOpaque(self.inner) as *mut ContextSettings;
+            if !settings.is_null() {
+                drop(Box::from_raw(settings));
+            }
+            let runtime = JS_GetRuntime(self.inner);
+            JS_FreeContext(self.inner);
+            JS_RunGC(runtime);
+
+            let inner = JS_NewContext(runtime);
+            if inner.is_null() {
+                bail!("Couldn't create JavaScript context");
+            }
+            self.inner = inner;
+        }
+        Ok(())
+    }
+
+    /// Evaluates JavaScript code in the global scope and deserializes its completion value into
+    /// `T`.
+    ///
+    /// A script whose completion value is `undefined`, like one ending with a declaration,
+    /// deserializes into `()` or `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// let sum: u32 = context.eval_to("test.js", "1 + 1")?;
+    /// ```
+    pub fn eval_to<T: DeserializeOwned>(&self, name: &str, contents: &str) -> Result<T> {
+        let value = self.eval_global(name, contents)?;
+        let mut deserializer = Deserializer::from(value);
+        Ok(T::deserialize(&mut deserializer)?)
+    }
+
+    /// Evaluates JavaScript code in the global scope, executes the pending jobs it queued and fails
+    /// if any promise was rejected without a handler.
+    ///
+    /// Unlike [`Self::eval_global`] followed by [`Self::execute_pending`], a rejection from async
+    /// code is not swallowed: the first unhandled rejection is returned as an error once there are
+    /// no more pending jobs. A rejection that gets a handler attached before then is ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// let result = context.eval_global_and_execute_pending(
+    ///     "test.js",
+    ///     "(async () => { throw new Error('boom'); })()",
+    /// );
+    /// assert!(result.is_err());
+    /// ```
+    pub fn eval_global_and_execute_pending(
+        &self,
+        name: &str,
+        contents: &str,
+    ) -> Result<JSValueRef> {
+        let tracking = RejectionTracking::start(self);
+
+        let value = self.eval_global(name, contents)?;
+        self.execute_pending()?;
+
+        match tracking.first_reason() {
+            Some(reason) => bail!("Uncaught (in promise) {reason}"),
+            None => Ok(value),
+        }
+    }
+
+    /// Evaluates JavaScript code in the global scope, stopping it once it has used up `fuel`.
+    ///
+    /// QuickJS polls its interrupt handler after roughly every 10,000 function calls and loop
+    /// iterations, and each poll consumes one unit of fuel. Unlike a wall-clock deadline, this
+    /// makes the limit reproducible across machines, but the amount of work allowed per unit
+    /// depends on the code being run so limits are best calibrated against representative
+    /// scripts.
+    ///
+    /// Running out of fuel fails with an uncatchable `InternalError: interrupted`. The limit only
+    /// applies to this evaluation: the runtime's previous interrupt handler is put back once it
+    /// returns, whether it succeeded or not.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// assert!(context.eval_global_with_fuel("test.js", "for (;;) {}", 100).is_err());
+    /// ```
+    pub fn eval_global_with_fuel(
+        &self,
+        name: &str,
+        contents: &str,
+        fuel: u64,
+    ) -> Result<JSValueRef> {
+        let fuel = Fuel {
+            remaining: Cell::new(fuel),
+            exhausted: Cell::new(false),
+        };
+        let runtime = unsafe { JS_GetRuntime(self.inner) };
+        let mut previous_opaque = ptr::null_mut();
+        let previous = unsafe { JS_GetInterruptHandler_Ext(runtime, &mut previous_opaque) };
+        unsafe {
+            JS_SetInterruptHandler(
+                runtime,
+                Some(consume_fuel),
+                &fuel as *const Fuel as *mut c_void,
+            )
+        };
+        let result = self.eval_global(name, contents);
+        unsafe { JS_SetInterruptHandler(runtime, previous, previous_opaque) };
+        if !fuel.exhausted.get() {
+            return result;
+        }
+        // The interrupt can't be caught, so the script failed because it ran out of fuel.
+        result.map_err(|err| match err.downcast::<Exception>() {
+            Ok(mut exception) => {
+                exception.mark_interrupted();
+                anyhow::Error::new(exception)
+            }
+            Err(err) => err,
+        })
+    }
+
+    /// Sets the function used to get the source code of the modules imported by ES modules.
+    ///
+    /// The loader is called once per module with its normalized name, see [`modules`] for
+    /// prebuilt loaders. If it fails, the `import` throws a `ReferenceError` including the
+    /// loader's error. Setting a new loader replaces the previous one.
+    ///
+    /// [`modules`]: super::modules
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// context.set_module_loader(|name| match name {
+    ///     "greeting.js" => Ok("export default 'hello';".to_string()),
+    ///     _ => bail!("Unknown module"),
+    /// });
+    /// context.eval_module("main.js", "import greeting from './greeting.js';")?;
+    /// ```
+    pub fn set_module_loader<F>(&self, loader: F)
+    where
+        F: Fn(&str) -> Result<String> + 'static,
+    {
+        let runtime = unsafe { JS_GetRuntime(self.inner) };
+        let loader: *mut ModuleLoader = Box::into_raw(Box::new(Box::new(loader)));
+        unsafe { JS_SetModuleLoaderFunc(runtime, None, Some(load_module), loader as *mut c_void) };
+
+        // The previous loader can't be called anymore, so it's freed.
+        let previous = MODULE_LOADERS
+            .lock()
+            .unwrap()
+            .insert(runtime as usize, loader as usize);
+        if let Some(previous) = previous {
+            drop(unsafe { Box::from_raw(previous as *mut ModuleLoader) });
+        }
+    }
+
+    /// Evaluates a CommonJS module and returns its `module.exports`.
+    ///
+    /// The source is evaluated as the body of a function receiving `exports`, `require` and
+    /// `module`, like in Node.js. Calling `require(name)` gets the source of the required module
+    /// from `require`, which takes the name as given to `require` and can be one of the loaders in
+    /// [`modules`]. Each module is only evaluated once, later calls and circular requires return
+    /// the same exports. If the loader fails, `require` throws a `ReferenceError` including the
+    /// loader's error.
+    ///
+    /// [`modules`]: super::modules
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// let exports = context.eval_commonjs(
+    ///     "main.js",
+    ///     "const { TWO } = require('constants.js'); module.exports = (n) => n * TWO;",
+    ///     modules::in_memory([("constants.js", "exports.TWO = 2;")]),
+    /// )?;
+    /// ```
+    pub fn eval_commonjs<F>(&self, name: &str, source: &str, require: F) -> Result<JSValueRef>
+    where
+        F: Fn(&str) -> Result<String> + 'static,
+    {
+        let modules = Rc::new(CommonJsModules {
+            loader: Box::new(require),
+            cache: RefCell::new(HashMap::new()),
+        });
+        self.eval_commonjs_module(name, source, &modules)
+    }
+
+    fn eval_commonjs_module(
+        &self,
+        name: &str,
+        source: &str,
+        modules: &Rc<CommonJsModules>,
+    ) -> Result<JSValueRef> {
+        // The function's header stays on the first line, so line numbers in errors match the
+        // source.
+        let function = self.eval_global(
+            name,
+            &format!("(function (exports, require, module) {{{source}\n}})"),
+        )?;
+        let module = self.object_value()?;
+        module.set_property("exports", self.object_value()?)?;
+        modules
+            .cache
+            .borrow_mut()
+            .insert(name.to_string(), module.value);
+
+        let require_modules = modules.clone();
+        let require = self.new_callback(move |inner, _this, argc, argv, _magic| {
+            let context = JSContextRef { inner };
+            let name = if argc > 0 {
+                JSValueRef::new_unchecked(&context, unsafe { *argv })
+                    .as_str_lossy()
+                    .into_owned()
+            } else {
+                "undefined".to_string()
+            };
+            let cached = require_modules.cache.borrow().get(&name).copied();
+            let exports = match cached {
+                Some(module) => JSValueRef::new_unchecked(&context, module).get_property("exports"),
+                None => (require_modules.loader)(&name).and_then(|source| {
+                    context.eval_commonjs_module(&name, &source, &require_modules)
+                }),
+            };
+            match exports {
+                Ok(exports) => exports.value,
+                Err(err) => context.throw_module_load_error(&name, &err),
+            }
+        })?;
+
+        function.call(
+            &self.undefined_value()?,
+            &[module.get_property("exports")?, require, module],
+        )?;
+        module.get_property("exports")
+    }
+
     /// Evaluates JavaScript code in an ECMAScript module scope.
     ///
     /// This method takes JavaScript code as a string and evaluates it in a
@@ -128,6 +718,12 @@ impl JSContextRef {
         if compile_only {
             eval_flags |= JS_EVAL_FLAG_COMPILE_ONLY;
         }
+        if self
+            .settings()
+            .is_some_and(|settings| settings.strict.get())
+        {
+            eval_flags |= JS_EVAL_FLAG_STRICT;
+        }
         let raw = unsafe {
             JS_Eval(
                 self.inner,
@@ -161,6 +757,22 @@ impl JSContextRef {
         self.compile(name, contents, EvalType::Global)
     }
 
+    /// Checks that `contents` parses as a script with a global scope, without running it.
+    ///
+    /// The script is compiled and the result is discarded, so this returns the `SyntaxError` that
+    /// [`Self::eval_global`] would fail with, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// assert!(context.check_syntax("test.js", "1 +").is_err());
+    /// ```
+    pub fn check_syntax(&self, name: &str, contents: &str) -> Result<()> {
+        self.eval(name, contents, EvalType::Global, true)?;
+        Ok(())
+    }
+
     fn compile(&self, name: &str, contents: &str, compile_as: EvalType) -> Result<Vec<u8>> {
         let raw = self.eval(name, contents, compile_as, true)?;
 
@@ -203,30 +815,283 @@ impl JSContextRef {
     /// until there are no more pending jobs or an exception occurs. It returns a `Result` indicating
     /// whether the execution was successful or an error if an exception was thrown.
     pub fn execute_pending(&self) -> Result<()> {
+        while self.execute_pending_job()? {}
+        Ok(())
+    }
+
+    /// Executes the next pending job, returning `false` if there was none.
+    fn execute_pending_job(&self) -> Result<bool> {
         let runtime = unsafe { JS_GetRuntime(self.inner) };
+        let mut ctx = ptr::null_mut();
+        match unsafe { JS_ExecutePendingJob(runtime, &mut ctx) } {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Exception::new(self)?.into_error()),
+        }
+    }
+
+    /// Evaluates JavaScript code in the global scope and, if it returns a promise, runs pending
+    /// jobs until the promise settles, returning its value.
+    ///
+    /// Jobs are run in batches, yielding to the Tokio executor between batches so other tasks on
+    /// the same thread make progress while a long chain of promises is resolved. Since contexts
+    /// aren't `Send`, the returned future isn't either, and has to be run on a
+    /// [`tokio::task::LocalSet`] or a current-thread runtime. A rejected promise fails with its
+    /// reason. If no jobs are left and the promise is still pending, nothing can settle it
+    /// anymore, which is reported as an error too.
+    ///
+    /// Only available with the `tokio` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let local = tokio::task::LocalSet::new();
+    /// local.block_on(&runtime, async {
+    ///     let context = JSContextRef::default();
+    ///     let value = context.eval_async("main.js", "(async () => 42)()").await?;
+    ///     assert_eq!(42, value.as_i32_unchecked());
+    /// });
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn eval_async(&self, name: &str, contents: &str) -> Result<JSValueRef> {
+        let value = self.eval_global(name, contents)?;
+        if !value.is_instance_of_global("Promise")? {
+            return Ok(value);
+        }
+
+        // The promise's outcome is recorded on a plain object so no Rust callback has to keep a
+        // reference to the settled value.
+        let state = self.object_value()?;
+        let watch = self.eval_global(
+            name,
+            "(function (promise, state) {\
+                promise.then(\
+                    (value) => { state.fulfilled = true; state.value = value; },\
+                    (reason) => { state.rejected = true; state.reason = reason; });\
+            })",
+        )?;
+        watch.call(&self.undefined_value()?, &[value, state])?;
 
         loop {
-            let mut ctx = ptr::null_mut();
-            match unsafe { JS_ExecutePendingJob(runtime, &mut ctx) } {
-                0 => break Ok(()),
-                1 => (),
-                _ => break Err(Exception::new(self)?.into_error()),
+            for _ in 0..JOBS_PER_BATCH {
+                if !self.execute_pending_job()? {
+                    break;
+                }
+            }
+            if state.get_property("fulfilled")?.is_bool() {
+                return state.get_property("value");
+            }
+            if state.get_property("rejected")?.is_bool() {
+                let reason = Exception::from(state.get_property("reason")?)?;
+                bail!("Uncaught (in promise) {reason}");
             }
+            if !self.is_pending() {
+                bail!("Promise returned by {name} never settled");
+            }
+            tokio::task::yield_now().await;
         }
     }
 
+    /// Returns a snapshot of the memory used by the context's runtime.
+    ///
+    /// This is read-only telemetry meant to be reported to the host, see [`MemoryStats`].
+    pub fn memory_usage(&self) -> MemoryStats {
+        let mut usage = MaybeUninit::<JSMemoryUsage>::uninit();
+        unsafe {
+            JS_ComputeMemoryUsage(JS_GetRuntime(self.inner), usage.as_mut_ptr());
+            usage.assume_init().into()
+        }
+    }
+
+    /// Parses JSON read from `reader` into a JavaScript value.
+    ///
+    /// The input is read in chunks into a single buffer which is then handed to QuickJS's JSON
+    /// parser. Reading stops with an error as soon as more than `max_len` bytes have been read, so
+    /// an unbounded reader can't exhaust the available memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader`: The source of the JSON text.
+    /// * `max_len`: The maximum number of bytes to read.
+    pub fn parse_json_reader(&self, mut reader: impl Read, max_len: usize) -> Result<JSValueRef> {
+        let mut buffer = Vec::new();
+        let mut chunk = [0; JSON_READ_CHUNK_SIZE];
+        loop {
+            let read = match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if buffer.len() + read > max_len {
+                bail!("JSON input exceeds the maximum length of {max_len} bytes");
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+        }
+        self.parse_json_bytes(buffer)
+    }
+
+    /// Parses each line of `reader` as JSON and deserializes it into `T`, for JSON Lines input
+    /// like NDJSON streamed through stdin.
+    ///
+    /// Lines are read one at a time as the iterator advances, so the whole stream is never held
+    /// in memory. Blank lines are skipped, and `\r\n` line endings are accepted. A line that
+    /// can't be parsed or deserialized yields an error naming its line number and iteration
+    /// continues with the next line. The iterator ends after an I/O error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// for event in context.deserialize_json_lines::<Event, _>(io::stdin().lock()) {
+    ///     handle(event?);
+    /// }
+    /// ```
+    pub fn deserialize_json_lines<'a, T, R>(
+        &'a self,
+        reader: R,
+    ) -> impl Iterator<Item = Result<T>> + 'a
+    where
+        T: DeserializeOwned,
+        R: BufRead + 'a,
+    {
+        let mut lines = reader.split(b'\n').enumerate();
+        let mut failed = false;
+        std::iter::from_fn(move || loop {
+            if failed {
+                return None;
+            }
+            let (index, line) = lines.next()?;
+            let mut line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    failed = true;
+                    return Some(Err(e.into()));
+                }
+            };
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            let item = self.parse_json_bytes(line).and_then(|value| {
+                let mut deserializer = Deserializer::from(value);
+                Ok(T::deserialize(&mut deserializer)?)
+            });
+            return Some(item.with_context(|| format!("line {}", index + 1)));
+        })
+    }
+
+    fn parse_json_bytes(&self, mut json: Vec<u8>) -> Result<JSValueRef> {
+        // Validating the complete buffer instead of each chunk means multi-byte characters that
+        // were split across reads are handled correctly.
+        str::from_utf8(&json).context("JSON input is not valid UTF-8")?;
+        let len = json.len();
+        // QuickJS expects the input to be null terminated.
+        json.push(0);
+        let name = CString::new("<json>")?;
+        let raw = unsafe {
+            JS_ParseJSON(
+                self.inner,
+                json.as_ptr() as *const c_char,
+                len as _,
+                name.as_ptr(),
+            )
+        };
+        JSValueRef::new(self, raw)
+    }
+
+    /// Parses JSON5 text into a JavaScript value.
+    ///
+    /// JSON5 allows comments, trailing commas, unquoted keys and single quoted strings, which is
+    /// convenient for hand-written configuration. The text is handled by a JSON5 parser instead
+    /// of being evaluated, so parsing can never execute guest code.
+    #[cfg(feature = "json5")]
+    pub fn parse_json5(&self, text: &str) -> Result<JSValueRef> {
+        let mut deserializer = json5::Deserializer::from_str(text)?;
+        let mut serializer = crate::serialize::ser::Serializer::from_context(self)?;
+        serde_transcode::transcode(&mut deserializer, &mut serializer)?;
+        Ok(serializer.value)
+    }
+
     /// Retrieves the global object of the JavaScript context.
     pub fn global_object(&self) -> Result<JSValueRef> {
         let raw = unsafe { JS_GetGlobalObject(self.inner) };
         JSValueRef::new(self, raw)
     }
 
+    /// Creates a JavaScript `Date` for the given number of milliseconds since the Unix epoch.
+    ///
+    /// This is the equivalent of `new Date(millis)`, so times outside of the range supported by
+    /// `Date` create an invalid date.
+    pub fn date_from_epoch_millis(&self, millis: i64) -> Result<JSValueRef> {
+        let constructor = self.global_object()?.get_property("Date")?;
+        if !constructor.is_function() {
+            bail!("Date is not available in this context");
+        }
+
+        let mut args = [self.value_from_f64(millis as f64)?.value];
+        let raw = unsafe {
+            JS_CallConstructor(
+                self.inner,
+                constructor.value,
+                args.len() as i32,
+                args.as_mut_ptr(),
+            )
+        };
+        JSValueRef::new(self, raw)
+    }
+
+    /// Creates a new, empty JavaScript `Map`, like `new Map()`.
+    pub fn new_map(&self) -> Result<JSValueRef> {
+        let constructor = self.global_object()?.get_property("Map")?;
+        if !constructor.is_function() {
+            bail!("Map is not available in this context");
+        }
+
+        let raw = unsafe { JS_CallConstructor(self.inner, constructor.value, 0, ptr::null_mut()) };
+        JSValueRef::new(self, raw)
+    }
+
     /// Creates a new JavaScript Array object.
+    ///
+    /// An older name for [`JSContextRef::new_array`], which new code should use instead.
     pub fn array_value(&self) -> Result<JSValueRef> {
+        self.new_array()
+    }
+
+    /// Creates a new, empty JavaScript array, like `[]`.
+    ///
+    /// This is the way to build arrays from the host. Elements can be added with
+    /// [`JSValueRef::push`] or [`JSValueRef::append_property`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let array = context.new_array()?;
+    /// array.push(context.value_from_i32(1)?)?;
+    /// array.push(context.value_from_str("two")?)?;
+    /// ```
+    pub fn new_array(&self) -> Result<JSValueRef> {
         let raw = unsafe { JS_NewArray(self.inner) };
         JSValueRef::new(self, raw)
     }
 
+    /// Creates a new, empty JavaScript array that `capacity` elements are going to be added to.
+    ///
+    /// `capacity` is only a hint. QuickJS doesn't expose a way to reserve storage for an array's
+    /// elements, so this creates the array with `JS_NewArray` like [`JSContextRef::new_array`]
+    /// and leaves its `length` at 0 rather than setting it to `capacity`, which would fill it with
+    /// holes. The array grows as elements are added. Returns an error if `capacity` is larger than
+    /// the maximum array length.
+    pub fn new_array_with_capacity(&self, capacity: usize) -> Result<JSValueRef> {
+        if u32::try_from(capacity).is_err() {
+            bail!("Array capacity {capacity} is larger than the maximum array length");
+        }
+        self.new_array()
+    }
+
     /// Creates a new JavaScript ArrayBuffer object with the specified bytes.
     pub fn array_buffer_value(&self, bytes: &[u8]) -> Result<JSValueRef> {
         JSValueRef::new(self, unsafe {
@@ -235,7 +1100,24 @@ impl JSContextRef {
     }
 
     /// Creates a new JavaScript Object.
+    ///
+    /// An older name for [`JSContextRef::new_object`], which new code should use instead.
     pub fn object_value(&self) -> Result<JSValueRef> {
+        self.new_object()
+    }
+
+    /// Creates a new, empty JavaScript object, like `{}`.
+    ///
+    /// This is the way to build objects from the host. Properties can be added with
+    /// [`JSValueRef::set_property`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let object = context.new_object()?;
+    /// object.set_property("name", context.value_from_str("javy")?)?;
+    /// ```
+    pub fn new_object(&self) -> Result<JSValueRef> {
         let raw = unsafe { JS_NewObject(self.inner) };
         JSValueRef::new(self, raw)
     }
@@ -418,6 +1300,61 @@ impl JSContextRef {
         JSValueRef::new(self, raw)
     }
 
+    /// Throws `value` as a JavaScript exception.
+    ///
+    /// This is meant for callbacks created with [`JSContextRef::new_callback`]: the returned value
+    /// is the exception sentinel, and returning it from the callback makes the call throw `value`,
+    /// which guest code can catch with `try`/`catch`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let callback = context.new_callback(|inner, _this, argc, _argv, _magic| {
+    ///     let context = JSContextRef { inner };
+    ///     if argc == 0 {
+    ///         return context.throw_type_error("expected an argument");
+    ///     }
+    ///     context.undefined_value().unwrap().value
+    /// })?;
+    /// ```
+    pub fn throw(&self, value: JSValueRef) -> JSValue {
+        unsafe { JS_Throw(self.inner, value.value) }
+    }
+
+    /// Throws a `TypeError` with `message`, like [`JSContextRef::throw`].
+    pub fn throw_type_error(&self, message: &str) -> JSValue {
+        self.throw_error(JS_ThrowTypeError, message)
+    }
+
+    /// Throws a `RangeError` with `message`, like [`JSContextRef::throw`].
+    pub fn throw_range_error(&self, message: &str) -> JSValue {
+        self.throw_error(JS_ThrowRangeError, message)
+    }
+
+    /// Throws the `ReferenceError` for a module that `import` or `require` couldn't load.
+    fn throw_module_load_error(&self, name: &str, err: &anyhow::Error) -> JSValue {
+        self.throw_error(
+            JS_ThrowReferenceError,
+            &format!("could not load module '{name}': {err}"),
+        )
+    }
+
+    fn throw_error(
+        &self,
+        throw: unsafe extern "C" fn(*mut JSContext, *const c_char, ...) -> JSValue,
+        message: &str,
+    ) -> JSValue {
+        // Messages are passed as a C string, so they end at the first null byte.
+        let message = CString::new(message).unwrap_or_else(|err| {
+            let len = err.nul_position();
+            let mut bytes = err.into_vec();
+            bytes.truncate(len);
+            CString::new(bytes).unwrap()
+        });
+        let format = CString::new("%s").unwrap();
+        unsafe { throw(self.inner, format.as_ptr(), message.as_ptr()) }
+    }
+
     /// Get the JS class ID used to wrap instances of the specified Rust type, or else create one if it doesn't
     /// already exist.
     fn get_class_id<T: 'static>(&self) -> JSClassID {
@@ -485,7 +1422,145 @@ where
         (get_rust_value::<F>(*data).unwrap().borrow_mut())(ctx, this, argc, argv, magic)
     }
 
-    Some(trampoline::<F>)
+    Some(trampoline::<F>)
+}
+
+/// Module loader for [`JSContextRef::set_module_loader`], with `opaque` pointing to the
+/// `ModuleLoader` to use.
+unsafe extern "C" fn load_module(
+    ctx: *mut JSContext,
+    module_name: *const c_char,
+    opaque: *mut c_void,
+) -> *mut JSModuleDef {
+    let loader = &*(opaque as *const ModuleLoader);
+    let context = JSContextRef { inner: ctx };
+    let name = CStr::from_ptr(module_name).to_string_lossy();
+
+    match loader(&name).and_then(|source| context.eval(&name, &source, EvalType::Module, true)) {
+        Ok(module) => JS_GetModuleDef_Ext(module.value),
+        Err(err) => {
+            context.throw_module_load_error(&name, &err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// The fuel of a [`JSContextRef::eval_global_with_fuel`] call.
+struct Fuel {
+    remaining: Cell<u64>,
+    /// Set once the interrupt handler has stopped the script.
+    exhausted: Cell<bool>,
+}
+
+/// Interrupt handler for [`JSContextRef::eval_global_with_fuel`], with `opaque` pointing to its
+/// [`Fuel`].
+unsafe extern "C" fn consume_fuel(_runtime: *mut JSRuntime, opaque: *mut c_void) -> c_int {
+    let fuel = &*(opaque as *const Fuel);
+    match fuel.remaining.get() {
+        0 => {
+            fuel.exhausted.set(true);
+            1
+        }
+        remaining => {
+            fuel.remaining.set(remaining - 1);
+            0
+        }
+    }
+}
+
+/// Tracks unhandled promise rejections in a runtime for
+/// [`JSContextRef::eval_global_and_execute_pending`].
+///
+/// Dropping it restores the rejection tracker the runtime had before and releases the rejections
+/// recorded so far, so nothing is left behind when the evaluation fails.
+struct RejectionTracking {
+    context: *mut JSContext,
+    runtime: *mut JSRuntime,
+    previous: (JSHostPromiseRejectionTracker, *mut c_void),
+}
+
+impl RejectionTracking {
+    fn start(context: &JSContextRef) -> Self {
+        let runtime = unsafe { JS_GetRuntime(context.inner) };
+        let mut opaque = ptr::null_mut();
+        let tracker = unsafe { JS_GetHostPromiseRejectionTracker_Ext(runtime, &mut opaque) };
+        let tracking = Self {
+            context: context.inner,
+            runtime,
+            previous: (tracker, opaque),
+        };
+        unsafe {
+            JS_SetHostPromiseRejectionTracker(runtime, Some(track_rejection), ptr::null_mut())
+        };
+        tracking
+    }
+
+    /// Returns the formatted reason of the first rejection still unhandled.
+    fn first_reason(&self) -> Option<String> {
+        UNHANDLED_REJECTIONS
+            .lock()
+            .unwrap()
+            .get(&(self.runtime as usize))
+            .and_then(|rejections| rejections.first())
+            .map(|(_, reason)| reason.clone())
+    }
+
+    fn release_rejections(&self) {
+        let rejections = UNHANDLED_REJECTIONS
+            .lock()
+            .unwrap()
+            .remove(&(self.runtime as usize))
+            .unwrap_or_default();
+        for (promise, _) in rejections {
+            unsafe { JS_FreeValue_Ext(self.context, promise) };
+        }
+    }
+}
+
+impl Drop for RejectionTracking {
+    fn drop(&mut self) {
+        let (tracker, opaque) = self.previous;
+        unsafe { JS_SetHostPromiseRejectionTracker(self.runtime, tracker, opaque) };
+        self.release_rejections();
+    }
+}
+
+/// Records unhandled promise rejections for
+/// [`JSContextRef::eval_global_and_execute_pending`].
+///
+/// QuickJS calls this with `is_handled` set to false when a promise without handlers is rejected,
+/// and again with it set to true if a handler is attached later on.
+unsafe extern "C" fn track_rejection(
+    ctx: *mut JSContext,
+    promise: JSValue,
+    reason: JSValue,
+    is_handled: c_int,
+    _opaque: *mut c_void,
+) {
+    let runtime = JS_GetRuntime(ctx) as usize;
+    if is_handled != 0 {
+        if let Some(rejections) = UNHANDLED_REJECTIONS.lock().unwrap().get_mut(&runtime) {
+            if let Some(index) = rejections
+                .iter()
+                .position(|(rejected, _)| *rejected == promise)
+            {
+                let (rejected, _) = rejections.remove(index);
+                JS_FreeValue_Ext(ctx, rejected);
+            }
+        }
+        return;
+    }
+
+    let context = JSContextRef { inner: ctx };
+    let reason = Exception::from(JSValueRef::new_unchecked(&context, reason))
+        .map(|exception| exception.to_string())
+        .unwrap_or_else(|_| "unknown reason".to_string());
+    UNHANDLED_REJECTIONS
+        .lock()
+        .unwrap()
+        .entry(runtime)
+        .or_default()
+        .push((JS_DupValue_Ext(ctx, promise), reason));
 }
 
 fn get_rust_value<T: 'static>(raw: JSValue) -> Result<&'static RefCell<T>> {
@@ -510,11 +1585,19 @@ enum EvalType {
 
 #[cfg(test)]
 mod tests {
-    use super::JSContextRef;
-    use crate::JSError;
-    use anyhow::Result;
-    use quickjs_wasm_sys::ext_js_undefined;
+    use super::{Intrinsics, JSContextRef, JSValueRef, UNHANDLED_REJECTIONS};
+    use crate::{modules, Deserializer, Exception, JSError};
+    use anyhow::{bail, Result};
+    use quickjs_wasm_sys::{
+        ext_js_undefined, JSRuntime, JS_GetHostPromiseRejectionTracker_Ext,
+        JS_GetInterruptHandler_Ext, JS_GetRuntime, JS_SetInterruptHandler,
+    };
+    use serde::Deserialize;
     use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::io::{Cursor, Read};
+    use std::os::raw::{c_int, c_void};
+    use std::ptr;
     use std::rc::Rc;
     const SCRIPT_NAME: &str = "context.js";
 
@@ -524,6 +1607,41 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_with_intrinsics_leaves_out_disabled_built_ins() -> Result<()> {
+        let mut intrinsics = Intrinsics::default();
+        intrinsics.date(false).regexp(false);
+        let ctx = JSContextRef::with_intrinsics(&intrinsics)?;
+
+        let val = ctx.eval_global(SCRIPT_NAME, "[typeof Date, typeof RegExp, typeof Map]")?;
+        assert_eq!("undefined", val.get_indexed_property(0)?.as_str()?);
+        assert_eq!("undefined", val.get_indexed_property(1)?.as_str()?);
+        assert_eq!("function", val.get_indexed_property(2)?.as_str()?);
+
+        // Values still deserialize without the missing intrinsics.
+        let val = ctx.eval_global(SCRIPT_NAME, "({a: 1, b: 2})")?;
+        let map = HashMap::<String, i32>::deserialize(&mut Deserializer::from(val))?;
+        assert_eq!(Some(&2), map.get("b"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_intrinsics_without_eval_rejects_source() -> Result<()> {
+        let bytecode = JSContextRef::default().compile_global(SCRIPT_NAME, "var foo = 42;")?;
+
+        let mut intrinsics = Intrinsics::default();
+        intrinsics.eval(false);
+        let ctx = JSContextRef::with_intrinsics(&intrinsics)?;
+        assert!(ctx.eval_global(SCRIPT_NAME, "1 + 1").is_err());
+
+        ctx.eval_binary(&bytecode)?;
+        assert_eq!(
+            42,
+            ctx.global_object()?.get_property("foo")?.try_as_integer()?
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_context_evalutes_code_globally() -> Result<()> {
         let ctx = JSContextRef::default();
@@ -533,6 +1651,51 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_context_returns_the_completion_value() -> Result<()> {
+        let ctx = JSContextRef::default();
+        assert_eq!(2, ctx.eval_global(SCRIPT_NAME, "1+1")?.as_i32_unchecked());
+        for (contents, expected) in [
+            ("var a = 1; a * 3", "3"),
+            ("let b = 4; b", "4"),
+            ("if (true) { 'yes' } else { 'no' }", "yes"),
+            ("for (var i = 0; i < 3; i++) { i * 2 }", "4"),
+            ("function f() { return 5; } f()", "5"),
+        ] {
+            let val = ctx.eval_global(SCRIPT_NAME, contents)?;
+            assert_eq!(expected, val.coerce_to_string()?, "{contents}");
+        }
+
+        assert!(ctx.eval_global(SCRIPT_NAME, "var c = 1;")?.is_undefined());
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_with_args() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let args = [ctx.value_from_i32(2)?, ctx.value_from_f64(3.5)?];
+        let sum = ctx.eval_with_args(SCRIPT_NAME, "arguments[0] + arguments[1]", &args)?;
+        assert_eq!(5.5, sum.as_f64()?);
+
+        let count = ctx.eval_with_args(SCRIPT_NAME, "arguments.length", &[])?;
+        assert_eq!(0, count.as_i32_unchecked());
+
+        assert!(ctx
+            .eval_with_args(SCRIPT_NAME, "throw new Error(arguments[0])", &args)
+            .is_err());
+        let leftover = ctx.eval_global(SCRIPT_NAME, "typeof arguments")?;
+        assert_eq!("undefined", leftover.as_str()?);
+
+        ctx.eval_global(SCRIPT_NAME, "var arguments = 'mine';")?;
+        let first = ctx.eval_with_args(SCRIPT_NAME, "arguments[0]", &args)?;
+        assert_eq!(2, first.as_i32_unchecked());
+        assert!(ctx
+            .eval_with_args(SCRIPT_NAME, "throw new Error(arguments[0])", &args)
+            .is_err());
+        assert_eq!("mine", ctx.eval_to::<String>(SCRIPT_NAME, "arguments")?);
+        Ok(())
+    }
+
     #[test]
     fn test_context_reports_invalid_code() -> Result<()> {
         let ctx = JSContextRef::default();
@@ -626,6 +1789,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_check_syntax() -> Result<()> {
+        let ctx = JSContextRef::default();
+        ctx.check_syntax(SCRIPT_NAME, "globalThis.foo = 42;")?;
+        assert!(ctx.global_object()?.get_property("foo")?.is_undefined());
+
+        let err = ctx.check_syntax(SCRIPT_NAME, "let x = ;").unwrap_err();
+        assert!(err.to_string().starts_with("Uncaught SyntaxError"));
+        Ok(())
+    }
+
     #[test]
     fn test_compile_module_errors_when_importing() -> Result<()> {
         let ctx = JSContextRef::default();
@@ -767,6 +1941,40 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_callbacks_can_throw() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let global = ctx.global_object()?;
+        global.set_property(
+            "parsePort",
+            ctx.new_callback(|inner, _this, argc, argv, _magic| {
+                let ctx = JSContextRef { inner };
+                if argc == 0 {
+                    return ctx.throw_type_error("expected a port");
+                }
+                let port = JSValueRef::new_unchecked(&ctx, unsafe { *argv });
+                match port.as_i32_unchecked() {
+                    0 => ctx.throw(ctx.value_from_str("port 0 is reserved").unwrap()),
+                    1..=65535 => port.value,
+                    _ => ctx.throw_range_error("port out of range\0ignored"),
+                }
+            })?,
+        )?;
+
+        let result = ctx.eval_global(
+            "main",
+            "[() => parsePort(), () => parsePort(0), () => parsePort(70000), () => parsePort(80)]\
+                .map((f) => { try { return f(); } catch (e) { \
+                    return e instanceof Error ? `${e.name}: ${e.message}` : e; } })\
+                .join()",
+        )?;
+        assert_eq!(
+            "TypeError: expected a port,port 0 is reserved,RangeError: port out of range,80",
+            result.as_str()?
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_wrap_callback_handles_error_messages_with_null_bytes() -> Result<()> {
         let ctx = JSContextRef::default();
@@ -805,4 +2013,595 @@ mod tests {
         assert!(ctx.is_pending());
         Ok(())
     }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_eval_async() -> Result<()> {
+        let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+        let local = tokio::task::LocalSet::new();
+        local.block_on(&runtime, async {
+            let ctx = JSContextRef::default();
+            let value = ctx
+                .eval_async("main", "(async () => { await null; return 40 + 2; })()")
+                .await?;
+            assert_eq!(42, value.as_i32_unchecked());
+
+            let value = ctx.eval_async("main", "'not a promise'").await?;
+            assert_eq!("not a promise", value.as_str()?);
+
+            let err = ctx
+                .eval_async("main", "Promise.reject(new Error('boom'))")
+                .await
+                .unwrap_err();
+            assert!(err.to_string().starts_with("Uncaught (in promise) Error: boom"));
+
+            let err = ctx.eval_async("main", "new Promise(() => {})").await;
+            assert!(err.is_err());
+
+            // Other tasks run while the promise chain is being resolved.
+            let ticks = Rc::new(Cell::new(0));
+            let task_ticks = ticks.clone();
+            let ticker = tokio::task::spawn_local(async move {
+                loop {
+                    task_ticks.set(task_ticks.get() + 1);
+                    tokio::task::yield_now().await;
+                }
+            });
+            let value = ctx
+                .eval_async(
+                    "main",
+                    "(async () => { let n = 0; for (let i = 0; i < 1000; i++) { n += await i; } return n; })()",
+                )
+                .await?;
+            assert_eq!(499500, value.as_i32_unchecked());
+            assert!(ticks.get() > 0);
+            ticker.abort();
+            Ok(())
+        })
+    }
+
+    /// A reader that returns at most 7 bytes per read so multi-byte characters end up split
+    /// across reads.
+    struct TrickleReader(Cursor<Vec<u8>>);
+
+    impl Read for TrickleReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(7);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    #[test]
+    fn test_parse_json_reader_parses_multi_megabyte_input() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let items = (0..100_000)
+            .map(|i| format!(r#"{{"id":{i},"text":"héllo ☕ wörld"}}"#))
+            .collect::<Vec<_>>();
+        let json = format!("[{}]", items.join(","));
+        assert!(json.len() > 3 * 1024 * 1024);
+
+        let val = ctx.parse_json_reader(TrickleReader(Cursor::new(json.into_bytes())), 8 << 20)?;
+
+        assert_eq!(100_000, val.get_property("length")?.try_as_integer()?);
+        let last = val.get_indexed_property(99_999)?;
+        assert_eq!(99_999, last.get_property("id")?.try_as_integer()?);
+        assert_eq!("héllo ☕ wörld", last.get_property("text")?.as_str()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_json_reader_errors_when_exceeding_max_len() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let json = format!("[{}]", vec!["1"; 10_000].join(","));
+        let err = ctx
+            .parse_json_reader(Cursor::new(json.into_bytes()), 1024)
+            .unwrap_err();
+        assert_eq!(
+            "JSON input exceeds the maximum length of 1024 bytes",
+            err.to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_json_lines() -> Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Event {
+            id: u32,
+            kind: String,
+        }
+
+        let ctx = JSContextRef::default();
+        let input = "{\"id\": 1, \"kind\": \"open\"}\n\
+                     \n   \n\
+                     {\"id\": 2, \n\
+                     {\"id\": 3, \"kind\": \"close\"}\r\n\
+                     {\"id\": \"four\", \"kind\": \"close\"}\n\
+                     {\"id\": 5, \"kind\": \"open\"}";
+        let events: Vec<Result<Event>> = ctx
+            .deserialize_json_lines(Cursor::new(input.as_bytes()))
+            .collect();
+        assert_eq!(5, events.len());
+
+        let event = |id, kind: &str| Event {
+            id,
+            kind: kind.to_string(),
+        };
+        assert_eq!(&event(1, "open"), events[0].as_ref().unwrap());
+        assert_eq!("line 4", events[1].as_ref().unwrap_err().to_string());
+        assert_eq!(&event(3, "close"), events[2].as_ref().unwrap());
+        assert_eq!("line 6", events[3].as_ref().unwrap_err().to_string());
+        assert_eq!(&event(5, "open"), events[4].as_ref().unwrap());
+
+        let cause = events[1].as_ref().unwrap_err().root_cause().to_string();
+        assert!(cause.starts_with("Uncaught SyntaxError"), "{cause}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_realm() -> Result<()> {
+        let first = JSContextRef::default();
+        let second = first.new_realm()?;
+        assert_eq!(unsafe { JS_GetRuntime(first.inner) }, unsafe {
+            JS_GetRuntime(second.inner)
+        });
+
+        first.eval_global("main", "globalThis.secret = 42; Array.prototype.extra = 1;")?;
+        let leaks = second.eval_global("main", "[typeof secret, typeof [].extra].join()")?;
+        assert_eq!("undefined,undefined", leaks.as_str()?);
+        assert_eq!(42, first.eval_global("main", "secret")?.as_i32_unchecked());
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_object() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let object = ctx.new_object()?;
+        assert!(object.is_object() && !object.is_array());
+        object.set_property("name", ctx.value_from_str("javy")?)?;
+        object.set_property("version", ctx.value_from_i32(3)?)?;
+        ctx.global_object()?.set_property("object", object)?;
+        let json = ctx.eval_global("main", "JSON.stringify(object)")?;
+        assert_eq!(r#"{"name":"javy","version":3}"#, json.as_str()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_array() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let array = ctx.new_array()?;
+        assert!(array.is_array());
+        assert_eq!(0, array.array_length()?);
+        array.push(ctx.value_from_i32(1)?)?;
+        array.push(ctx.value_from_str("two")?)?;
+        assert_eq!(2, array.array_length()?);
+
+        let array = ctx.new_array_with_capacity(3)?;
+        assert_eq!(0, array.array_length()?);
+        for i in 0..3 {
+            array.push(ctx.value_from_i32(i)?)?;
+        }
+        ctx.global_object()?.set_property("array", array)?;
+        let json = ctx.eval_global("main", "JSON.stringify(array)")?;
+        assert_eq!("[0,1,2]", json.as_str()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_global_error_name() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let err = ctx
+            .eval_global("main", "throw new RangeError('too far')")
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Uncaught RangeError: too far"));
+        let exception = err.downcast_ref::<Exception>().unwrap();
+        assert_eq!(Some("RangeError"), exception.name());
+
+        let err = ctx
+            .eval_global(
+                "main",
+                "class Oops extends Error { get name() { return 'Oops'; } }; throw new Oops()",
+            )
+            .unwrap_err();
+        assert_eq!(
+            Some("Oops"),
+            err.downcast_ref::<Exception>().unwrap().name()
+        );
+
+        let err = ctx.eval_global("main", "throw 'plain'").unwrap_err();
+        assert_eq!("Uncaught plain", err.to_string());
+        assert_eq!(None, err.downcast_ref::<Exception>().unwrap().name());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_json_reader_errors_on_invalid_input() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let err = ctx
+            .parse_json_reader(Cursor::new(b"{\"a\": }".to_vec()), 1024)
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Uncaught SyntaxError"));
+
+        let err = ctx
+            .parse_json_reader(Cursor::new(vec![b'"', 0xff, b'"']), 1024)
+            .unwrap_err();
+        assert_eq!("JSON input is not valid UTF-8", err.to_string());
+        Ok(())
+    }
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn test_parse_json5_accepts_comments_and_trailing_commas() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.parse_json5(
+            "
+            // Database settings
+            {
+                host: 'localhost', /* unquoted key, single quotes */
+                ports: [5432, 5433,],
+                options: { ssl: true, },
+            }",
+        )?;
+        assert_eq!("localhost", val.get_property("host")?.as_str()?);
+        let ports = val.get_property("ports")?;
+        assert_eq!(2, ports.get_property("length")?.try_as_integer()?);
+        assert_eq!(5433, ports.get_indexed_property(1)?.try_as_integer()?);
+        assert!(val
+            .get_property("options")?
+            .get_property("ssl")?
+            .as_bool()?);
+        Ok(())
+    }
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn test_parse_json5_does_not_evaluate_code() -> Result<()> {
+        let ctx = JSContextRef::default();
+        assert!(ctx.parse_json5("{ a: (globalThis.pwned = true) }").is_err());
+        assert!(ctx.global_object()?.get_property("pwned")?.is_undefined());
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_usage_grows_with_allocations() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let before = ctx.memory_usage();
+        assert!(before.malloc_size > 0);
+        assert!(before.malloc_count > 0);
+
+        ctx.eval_global(
+            "main",
+            "var objects = []; for (let i = 0; i < 1000; i++) objects.push({i, name: 'item' + i});",
+        )?;
+        let after = ctx.memory_usage();
+
+        assert!(after.malloc_size > before.malloc_size);
+        assert!(after.malloc_count > before.malloc_count);
+        assert!(after.obj_count >= before.obj_count + 1000);
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_to() -> Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Output {
+            a: u32,
+        }
+
+        let ctx = JSContextRef::default();
+        assert_eq!(Output { a: 1 }, ctx.eval_to::<Output>("main", "({a: 1})")?);
+        assert_eq!(3, ctx.eval_to::<u32>("main", "1 + 2")?);
+        ctx.eval_to::<()>("main", "var x = 1;")?;
+        assert_eq!(None, ctx.eval_to::<Option<u32>>("main", "undefined")?);
+
+        let err = ctx.eval_to::<Output>("main", "({a: 'one'})").unwrap_err();
+        assert_eq!(
+            "invalid type: string \"one\", expected u32",
+            err.to_string()
+        );
+        assert!(ctx.eval_to::<Output>("main", "throw 1").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_prelude() -> Result<()> {
+        let ctx = JSContextRef::default();
+        ctx.load_prelude(
+            "function double(n) { return n * 2; }\nconst GREETING = 'hello';\nvar calls = 0;",
+        )?;
+
+        assert_eq!(42, ctx.eval_to::<u32>("main", "calls++; double(21)")?);
+        assert_eq!(
+            "hello javy",
+            ctx.eval_to::<String>("main", "calls++; `${GREETING} javy`")?
+        );
+        assert_eq!(2, ctx.eval_to::<u32>("main", "calls")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_prelude_errors() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let err = ctx
+            .load_prelude("function broken( { return 1; }")
+            .unwrap_err();
+        assert_eq!("Failed to load the prelude", err.to_string());
+        assert!(format!("{err:#}").contains("SyntaxError"));
+
+        let err = ctx
+            .load_prelude("throw new Error('missing dependency')")
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("missing dependency"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_global_and_execute_pending_reports_unhandled_rejections() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let err = ctx
+            .eval_global_and_execute_pending(
+                "main",
+                "async function fail() { await null; throw new Error('boom'); }\nfail();",
+            )
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Uncaught (in promise) Error: boom"));
+
+        let err = ctx
+            .eval_global_and_execute_pending("main", "Promise.reject('nope')")
+            .unwrap_err();
+        assert_eq!("Uncaught (in promise) nope", err.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_global_and_execute_pending_ignores_handled_rejections() -> Result<()> {
+        let ctx = JSContextRef::default();
+        ctx.eval_global_and_execute_pending(
+            "main",
+            "var caught;\nconst p = Promise.reject(new Error('boom'));\np.catch((e) => { caught = e.message; });",
+        )?;
+        assert_eq!("boom", ctx.eval_to::<String>("main", "caught")?);
+
+        let value = ctx.eval_global_and_execute_pending("main", "40 + 2")?;
+        assert_eq!(42, value.as_i32_unchecked());
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_global_and_execute_pending_leaves_no_state_behind() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let runtime = unsafe { JS_GetRuntime(ctx.inner) } as usize;
+        assert!(ctx
+            .eval_global_and_execute_pending("main", "Promise.reject(1)")
+            .is_err());
+        assert!(ctx
+            .eval_global_and_execute_pending("main", "Promise.reject(1); throw 2")
+            .is_err());
+
+        ctx.eval_global("main", "Promise.reject(1)")?;
+        assert!(!UNHANDLED_REJECTIONS.lock().unwrap().contains_key(&runtime));
+
+        let mut opaque = ptr::null_mut();
+        let tracker = unsafe { JS_GetHostPromiseRejectionTracker_Ext(runtime as _, &mut opaque) };
+        assert!(tracker.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_global_with_fuel() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let iterations_with = |fuel| -> Result<f64> {
+            let err = ctx
+                .eval_global_with_fuel(
+                    "main",
+                    "var i = 0; for (;;) { try { i++; } catch (e) {} }",
+                    fuel,
+                )
+                .unwrap_err();
+            assert!(err
+                .to_string()
+                .starts_with("Uncaught InternalError: interrupted"));
+            assert!(err.downcast_ref::<Exception>().unwrap().is_interrupted());
+            ctx.global_object()?.get_property("i")?.as_f64()
+        };
+
+        // Without fuel, the script is stopped at the first poll.
+        let none = iterations_with(0)?;
+        let short = iterations_with(10)?;
+        let long = iterations_with(20)?;
+        assert!(none < short);
+        assert!(short < long);
+        assert!(none * 10.0 <= short);
+
+        // Enough fuel lets the script finish, and the limit doesn't outlive the evaluation.
+        let value = ctx.eval_global_with_fuel("main", "var n = 0; while (n < 1000) n++; n", 10)?;
+        assert_eq!(1000, value.as_i32_unchecked());
+        ctx.eval_global("main", "var m = 0; while (m < 10000000) m++;")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_global_with_fuel_restores_the_interrupt_handler() -> Result<()> {
+        unsafe extern "C" fn never_interrupt(
+            _runtime: *mut JSRuntime,
+            _opaque: *mut c_void,
+        ) -> c_int {
+            0
+        }
+
+        let ctx = JSContextRef::default();
+        let runtime = unsafe { JS_GetRuntime(ctx.inner) };
+        let mut marker = 0u8;
+        let marker = &mut marker as *mut u8 as *mut c_void;
+        unsafe { JS_SetInterruptHandler(runtime, Some(never_interrupt), marker) };
+        let assert_restored = || {
+            let mut opaque = ptr::null_mut();
+            let handler = unsafe { JS_GetInterruptHandler_Ext(runtime, &mut opaque) };
+            assert_eq!(
+                Some(never_interrupt as *const () as usize),
+                handler.map(|handler| handler as usize)
+            );
+            assert_eq!(marker, opaque);
+        };
+
+        assert!(ctx.eval_global_with_fuel("main", "for (;;) {}", 1).is_err());
+        assert_restored();
+        ctx.eval_global_with_fuel("main", "1 + 1", 1)?;
+        assert_restored();
+        assert!(ctx.eval_global_with_fuel("main", "throw 1", 1).is_err());
+        assert_restored();
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_module_loader() -> Result<()> {
+        let ctx = JSContextRef::default();
+        ctx.set_module_loader(modules::in_memory([
+            (
+                "math.js",
+                "import { TWO } from './lib/constants.js'; export const double = (n) => n * TWO;",
+            ),
+            ("lib/constants.js", "export const TWO = 2;"),
+            (
+                "even.js",
+                "import { isOdd } from './odd.js'; export const isEven = (n) => n === 0 || isOdd(n - 1);",
+            ),
+            (
+                "odd.js",
+                "import { isEven } from './even.js'; export const isOdd = (n) => n !== 0 && isEven(n - 1);",
+            ),
+        ]));
+
+        ctx.eval_module(
+            "main.js",
+            "import { double } from './math.js'; globalThis.result = double(21);",
+        )?;
+        assert_eq!(42, ctx.eval_to::<u32>("main", "result")?);
+
+        ctx.eval_module(
+            "main.js",
+            "import { isEven } from './even.js'; globalThis.even = isEven(10);",
+        )?;
+        assert!(ctx.eval_to::<bool>("main", "even")?);
+
+        let err = ctx
+            .eval_module("main.js", "import './missing.js';")
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("could not load module 'missing.js': Module `missing.js` not found"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_replaced_module_loaders_are_freed() -> Result<()> {
+        let mut ctx = JSContextRef::default();
+        let captured = Rc::new(());
+        let loader = |captured: Rc<()>| {
+            move |_: &str| -> Result<String> {
+                let _ = &captured;
+                Ok("export default 1;".to_string())
+            }
+        };
+
+        ctx.set_module_loader(loader(captured.clone()));
+        assert_eq!(2, Rc::strong_count(&captured));
+        ctx.set_module_loader(loader(captured.clone()));
+        assert_eq!(2, Rc::strong_count(&captured));
+        ctx.eval_module("main.js", "import one from 'one.js'; globalThis.one = one;")?;
+        assert_eq!(1, ctx.eval_to::<u32>("main", "one")?);
+
+        ctx.reset()?;
+        assert_eq!(2, Rc::strong_count(&captured));
+        ctx.set_module_loader(|_| bail!("no modules"));
+        assert_eq!(1, Rc::strong_count(&captured));
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_keeps_the_runtime_module_loader() -> Result<()> {
+        let mut ctx = JSContextRef::default();
+        let realm = ctx.new_realm()?;
+        ctx.set_module_loader(modules::in_memory([("one.js", "export default 1;")]));
+        ctx.force_strict(true);
+
+        ctx.reset()?;
+        ctx.eval_global("main", "undeclared = 1")?;
+        ctx.eval_module("main.js", "import one from 'one.js'; globalThis.one = one;")?;
+        assert_eq!(1, ctx.eval_to::<u32>("main", "one")?);
+        realm.eval_module("main.js", "import one from 'one.js'; globalThis.one = one;")?;
+        assert_eq!(1, realm.eval_to::<u32>("main", "one")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_commonjs() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let exports = ctx.eval_commonjs(
+            "main.js",
+            r#"
+            const math = require("math.js");
+            exports.sameInstance = require("math.js") === math;
+            module.exports = Object.assign((n) => math.double(n) + 1, exports);
+            "#,
+            modules::in_memory([
+                ("math.js", "exports.double = (n) => n * require('two.js');"),
+                ("two.js", "module.exports = 2;"),
+            ]),
+        )?;
+        assert!(exports.is_function());
+        let result = exports.call(&ctx.undefined_value()?, &[ctx.value_from_i32(20)?])?;
+        assert_eq!(41, result.as_i32_unchecked());
+        assert!(exports.get_property("sameInstance")?.as_bool()?);
+
+        let err = ctx
+            .eval_commonjs(
+                "main.js",
+                "require('missing.js');",
+                modules::in_memory([("a.js", "")]),
+            )
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("could not load module 'missing.js': Module `missing.js` not found"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_date_from_epoch_millis() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let date = ctx.date_from_epoch_millis(-1000)?;
+        assert!(date.is_instance_of_global("Date")?);
+        let to_iso_string = date.get_property("toISOString")?;
+        assert_eq!(
+            "1969-12-31T23:59:59.000Z",
+            to_iso_string.call(&date, &[])?.as_str()?
+        );
+
+        let mut intrinsics = Intrinsics::default();
+        intrinsics.date(false);
+        let ctx = JSContextRef::with_intrinsics(&intrinsics)?;
+        assert!(ctx.date_from_epoch_millis(0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_force_strict() -> Result<()> {
+        let ctx = JSContextRef::default();
+        ctx.eval_global("main", "undeclared = 1;")?;
+
+        ctx.force_strict(true);
+        let err = ctx.eval_global("main", "otherUndeclared = 1;").unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Uncaught ReferenceError: 'otherUndeclared' is not defined"));
+        let bytecode = ctx.compile_global("main", "function f() { return this; } f()")?;
+        assert!(ctx.eval_binary(&bytecode)?.is_undefined());
+
+        ctx.force_strict(false);
+        ctx.eval_global("main", "otherUndeclared = 1;")?;
+        Ok(())
+    }
 }
@@ -1,6 +1,6 @@
 use super::{context::JSContextRef, value::JSValueRef};
-use anyhow::{anyhow, Result};
-use quickjs_wasm_sys::{JS_GetException, JS_IsError};
+use anyhow::Result;
+use quickjs_wasm_sys::JS_GetException;
 use std::fmt;
 
 /// `Exception` represents a JavaScript exception that occurs within the QuickJS context.
@@ -16,14 +16,31 @@ use std::fmt;
 /// let exception = Exception::new(context)?;
 /// let err = Err(exception.into_error());
 /// ```
+///
+/// Errors returned by [`Exception::into_error`], like the ones from
+/// [`JSContextRef::eval_global`], can be downcast back to an `Exception`:
+///
+/// ```
+/// let err = context.eval_global("main.js", "null.x").unwrap_err();
+/// match err.downcast_ref::<Exception>().and_then(Exception::name) {
+///     Some("TypeError") => { /* ... */ }
+///     _ => { /* ... */ }
+/// }
+/// ```
 #[derive(Debug)]
 pub struct Exception {
     msg: String,
+    name: Option<String>,
     stack: Option<String>,
+    uncaught: bool,
+    interrupted: bool,
 }
 
 impl fmt::Display for Exception {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.uncaught {
+            write!(f, "Uncaught ")?;
+        }
         write!(f, "{}", self.msg)?;
         if let Some(stack) = &self.stack {
             write!(f, "\n{stack}")?;
@@ -32,6 +49,8 @@ impl fmt::Display for Exception {
     }
 }
 
+impl std::error::Error for Exception {}
+
 impl Exception {
     pub(super) fn new(context: &JSContextRef) -> Result<Self> {
         let exception_value = unsafe { JS_GetException(context.inner) };
@@ -40,20 +59,55 @@ impl Exception {
 
     pub fn from(exception_obj: JSValueRef) -> Result<Self> {
         let msg = exception_obj.as_str().map(ToString::to_string)?;
+        let mut name = None;
         let mut stack = None;
 
-        let is_error = unsafe { JS_IsError(exception_obj.context.inner, exception_obj.value) } != 0;
-        if is_error {
+        if exception_obj.is_object() {
+            let name_value = exception_obj.get_property("name")?;
+            if name_value.is_str() {
+                name.replace(name_value.as_str().map(ToString::to_string)?);
+            }
+        }
+
+        if exception_obj.is_error() {
             let stack_value = exception_obj.get_property("stack")?;
             if !stack_value.is_undefined() {
                 stack.replace(stack_value.as_str().map(ToString::to_string)?);
             }
         }
 
-        Ok(Exception { msg, stack })
+        Ok(Exception {
+            msg,
+            name,
+            stack,
+            uncaught: false,
+            interrupted: false,
+        })
+    }
+
+    /// Returns the `name` property of the thrown value, like `"TypeError"`, if it's an object
+    /// with a string `name`.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns `true` if the exception was thrown because QuickJS was interrupted, like when
+    /// [`JSContextRef::eval_global_with_fuel`] runs out of fuel.
+    ///
+    /// Guest code can throw an `InternalError` with the same message, but that doesn't count as
+    /// an interrupt.
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    pub(super) fn mark_interrupted(&mut self) {
+        self.interrupted = true;
     }
 
-    pub fn into_error(self) -> anyhow::Error {
-        anyhow!("Uncaught {}", self)
+    /// Converts the exception into an error reporting it as uncaught. The error can be downcast
+    /// back to an `Exception`.
+    pub fn into_error(mut self) -> anyhow::Error {
+        self.uncaught = true;
+        anyhow::Error::new(self)
     }
 }
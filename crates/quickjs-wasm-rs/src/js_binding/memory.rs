@@ -0,0 +1,99 @@
+use quickjs_wasm_sys::JSMemoryUsage;
+
+/// `MemoryStats` is a snapshot of the memory used by a QuickJS runtime.
+///
+/// The values are read from QuickJS's `JS_ComputeMemoryUsage`. Counts are the
+/// number of live allocations or objects of a given kind and sizes are in
+/// bytes. Since contexts created with `JSContextRef::default` each own their
+/// runtime, the stats cover everything allocated by that context.
+///
+/// # Example
+///
+/// ```
+/// let context = JSContextRef::default();
+/// let stats = context.memory_usage();
+/// println!("{} bytes in {} allocations", stats.malloc_size, stats.malloc_count);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Number of bytes currently allocated by the runtime's allocator.
+    pub malloc_size: i64,
+    /// Memory limit set on the runtime in bytes, or -1 if there is none.
+    pub malloc_limit: i64,
+    /// Number of allocations currently live.
+    pub malloc_count: i64,
+    /// Number of bytes used, including allocator overhead.
+    pub memory_used_size: i64,
+    /// Number of blocks used, including allocator bookkeeping.
+    pub memory_used_count: i64,
+    /// Number of atoms, the interned strings used for property names and identifiers.
+    pub atom_count: i64,
+    /// Number of bytes used by atoms, including the atom table.
+    pub atom_size: i64,
+    /// Number of strings held by values other than atoms.
+    pub str_count: i64,
+    /// Number of bytes used by strings other than atoms.
+    pub str_size: i64,
+    /// Number of objects, including arrays and functions.
+    pub obj_count: i64,
+    /// Number of bytes used by objects, not counting their properties.
+    pub obj_size: i64,
+    /// Number of properties defined on objects.
+    pub prop_count: i64,
+    /// Number of bytes used by the property storage of objects.
+    pub prop_size: i64,
+    /// Number of shapes, which describe the property layout shared by similar objects.
+    pub shape_count: i64,
+    /// Number of bytes used by shapes.
+    pub shape_size: i64,
+    /// Number of compiled JavaScript functions.
+    pub js_func_count: i64,
+    /// Number of bytes used by compiled JavaScript functions, not counting their bytecode.
+    pub js_func_size: i64,
+    /// Number of bytes of bytecode of compiled JavaScript functions.
+    pub js_func_code_size: i64,
+    /// Number of functions implemented in C, like built-ins and functions wrapping Rust closures.
+    pub c_func_count: i64,
+    /// Number of arrays.
+    pub array_count: i64,
+    /// Number of arrays whose elements are stored contiguously, usually arrays without holes.
+    pub fast_array_count: i64,
+    /// Number of elements stored in those arrays.
+    pub fast_array_elements: i64,
+    /// Number of bytecode buffers read so far with `JS_ReadObject`, like by
+    /// `JSContextRef::eval_binary`.
+    pub binary_object_count: i64,
+    /// Number of bytes of bytecode read so far with `JS_ReadObject`.
+    pub binary_object_size: i64,
+}
+
+impl From<JSMemoryUsage> for MemoryStats {
+    fn from(usage: JSMemoryUsage) -> Self {
+        Self {
+            malloc_size: usage.malloc_size,
+            malloc_limit: usage.malloc_limit,
+            malloc_count: usage.malloc_count,
+            memory_used_size: usage.memory_used_size,
+            memory_used_count: usage.memory_used_count,
+            atom_count: usage.atom_count,
+            atom_size: usage.atom_size,
+            str_count: usage.str_count,
+            str_size: usage.str_size,
+            obj_count: usage.obj_count,
+            obj_size: usage.obj_size,
+            prop_count: usage.prop_count,
+            prop_size: usage.prop_size,
+            shape_count: usage.shape_count,
+            shape_size: usage.shape_size,
+            js_func_count: usage.js_func_count,
+            js_func_size: usage.js_func_size,
+            js_func_code_size: usage.js_func_code_size,
+            c_func_count: usage.c_func_count,
+            array_count: usage.array_count,
+            fast_array_count: usage.fast_array_count,
+            fast_array_elements: usage.fast_array_elements,
+            binary_object_count: usage.binary_object_count,
+            binary_object_size: usage.binary_object_size,
+        }
+    }
+}
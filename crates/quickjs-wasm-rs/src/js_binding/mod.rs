@@ -2,5 +2,8 @@ pub mod constants;
 pub mod context;
 pub mod error;
 pub mod exception;
+pub mod memory;
+pub mod modules;
+pub mod pool;
 pub mod properties;
 pub mod value;
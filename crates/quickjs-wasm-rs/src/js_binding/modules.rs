@@ -0,0 +1,83 @@
+//! Module resolvers for use with [`JSContextRef::set_module_loader`].
+//!
+//! Module names are normalized by QuickJS before they are resolved, so an `import "./b.js"` from
+//! a module named `lib/a.js` asks for `lib/b.js`.
+//!
+//! [`JSContextRef::set_module_loader`]: super::context::JSContextRef::set_module_loader
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Resolves modules from a map of module names to their source code.
+///
+/// # Example
+///
+/// ```
+/// let context = JSContextRef::default();
+/// context.set_module_loader(modules::in_memory([("math.js", "export const two = 2;")]));
+/// context.eval_module("main.js", "import { two } from './math.js';")?;
+/// ```
+pub fn in_memory<I, K, V>(modules: I) -> impl Fn(&str) -> Result<String>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Into<String>,
+    V: Into<String>,
+{
+    let modules = modules
+        .into_iter()
+        .map(|(name, source)| (name.into(), source.into()))
+        .collect::<HashMap<String, String>>();
+    move |name| {
+        modules
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Module `{name}` not found"))
+    }
+}
+
+/// Resolves modules by reading them from files under `root`.
+///
+/// On WASI, `root` has to be in a directory that was preopened by the host. Module names that
+/// would resolve outside of `root`, like absolute paths or ones starting with `..`, are rejected.
+pub fn filesystem(root: impl Into<PathBuf>) -> impl Fn(&str) -> Result<String> {
+    let root = root.into();
+    move |name| {
+        let path = Path::new(name);
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            bail!("Module `{name}` is outside of the module root");
+        }
+        fs::read_to_string(root.join(path))
+            .with_context(|| format!("Failed to read module `{name}`"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filesystem, in_memory};
+
+    #[test]
+    fn test_in_memory() {
+        let resolve = in_memory([("a.js", "export default 1;")]);
+        assert_eq!("export default 1;", resolve("a.js").unwrap());
+        assert_eq!(
+            "Module `b.js` not found",
+            resolve("b.js").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_filesystem_stays_under_root() {
+        let resolve = filesystem("modules");
+        for name in ["../secret.js", "/etc/passwd", "lib/../../secret.js"] {
+            assert_eq!(
+                format!("Module `{name}` is outside of the module root"),
+                resolve(name).unwrap_err().to_string()
+            );
+        }
+    }
+}
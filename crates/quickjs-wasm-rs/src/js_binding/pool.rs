@@ -0,0 +1,135 @@
+use super::context::JSContextRef;
+use std::cell::RefCell;
+use std::ops::Deref;
+
+/// `ContextPool` keeps contexts around so they can be reused instead of creating a new runtime
+/// and context for every request.
+///
+/// A context is reset when its [`PooledContext`] is dropped: it's replaced by a new context in
+/// the same runtime and the garbage collector is run. Globals and changes to built-in prototypes
+/// made while it was checked out are gone, but the runtime's allocator, atoms, class
+/// registrations and settings like the module loader are reused. Contexts with pending jobs can't be reset safely and are discarded
+/// instead of being returned to the pool.
+///
+/// # Example
+///
+/// ```
+/// let pool = ContextPool::new(4);
+/// {
+///     let context = pool.checkout();
+///     context.eval_global("request.js", "globalThis.user = 'a';")?;
+/// }
+/// let context = pool.checkout();
+/// assert!(context.eval_global("request.js", "globalThis.user")?.is_undefined());
+/// ```
+#[derive(Debug)]
+pub struct ContextPool {
+    idle: RefCell<Vec<JSContextRef>>,
+    max_idle: usize,
+}
+
+impl ContextPool {
+    /// Creates an empty pool that keeps at most `max_idle` contexts between checkouts.
+    pub fn new(max_idle: usize) -> Self {
+        Self {
+            idle: RefCell::new(Vec::new()),
+            max_idle,
+        }
+    }
+
+    /// Returns an idle context, or a new one if the pool is empty.
+    pub fn checkout(&self) -> PooledContext<'_> {
+        let context = self.idle.borrow_mut().pop().unwrap_or_default();
+        PooledContext {
+            pool: self,
+            context: Some(context),
+        }
+    }
+
+    /// Returns the number of contexts waiting to be checked out.
+    pub fn idle_count(&self) -> usize {
+        self.idle.borrow().len()
+    }
+
+    fn release(&self, mut context: JSContextRef) {
+        let mut idle = self.idle.borrow_mut();
+        if idle.len() < self.max_idle && context.reset().is_ok() {
+            idle.push(context);
+        }
+    }
+}
+
+/// A context checked out of a [`ContextPool`], returned to the pool when dropped.
+#[derive(Debug)]
+pub struct PooledContext<'a> {
+    pool: &'a ContextPool,
+    context: Option<JSContextRef>,
+}
+
+impl Deref for PooledContext<'_> {
+    type Target = JSContextRef;
+
+    fn deref(&self) -> &JSContextRef {
+        self.context.as_ref().unwrap()
+    }
+}
+
+impl Drop for PooledContext<'_> {
+    fn drop(&mut self) {
+        if let Some(context) = self.context.take() {
+            self.pool.release(context);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ContextPool;
+    use anyhow::Result;
+    use quickjs_wasm_sys::JS_GetRuntime;
+
+    #[test]
+    fn test_checkout_does_not_see_previous_globals() -> Result<()> {
+        let pool = ContextPool::new(1);
+        let runtime = {
+            let context = pool.checkout();
+            context.eval_global(
+                "main",
+                "globalThis.secret = 42; var leaked = 1; Array.prototype.extra = 1;",
+            )?;
+            unsafe { JS_GetRuntime(context.inner) }
+        };
+        assert_eq!(1, pool.idle_count());
+
+        let context = pool.checkout();
+        assert_eq!(0, pool.idle_count());
+        assert_eq!(runtime, unsafe { JS_GetRuntime(context.inner) });
+        let leaks = context.eval_global(
+            "main",
+            "[typeof secret, typeof leaked, typeof [].extra].join()",
+        )?;
+        assert_eq!("undefined,undefined,undefined", leaks.as_str()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_context_with_pending_jobs_is_discarded() -> Result<()> {
+        let pool = ContextPool::new(1);
+        {
+            let context = pool.checkout();
+            context.eval_global("main", "Promise.resolve().then(() => {});")?;
+        }
+        assert_eq!(0, pool.idle_count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_idle() {
+        let pool = ContextPool::new(1);
+        let a = pool.checkout();
+        let b = pool.checkout();
+        drop(a);
+        drop(b);
+        assert_eq!(1, pool.idle_count());
+    }
+}
@@ -40,14 +40,27 @@ impl<'a> Properties<'a> {
         })
     }
 
+    /// Returns `true` if the object has no own enumerable properties.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
     pub fn next_key(&mut self) -> Result<Option<JSValueRef<'a>>> {
+        Ok(self
+            .next_atom()
+            .and_then(|atom| self.atom_to_string(atom).ok()))
+    }
+
+    /// Advances to the next property like [`Properties::next_key`] but returns the key's atom
+    /// instead of converting it to a string.
+    pub(crate) fn next_atom(&mut self) -> Option<JSAtom> {
         if self.offset >= self.length {
-            Ok(None)
+            None
         } else {
             let prop_enum = unsafe { self.property_enum.offset(self.offset) };
             self.offset += 1;
             self.current_key = unsafe { (*prop_enum).atom };
-            Ok(self.atom_to_string(self.current_key).ok())
+            Some(self.current_key)
         }
     }
 
@@ -64,7 +77,7 @@ impl<'a> Properties<'a> {
         JSValueRef::new(self.context, val)
     }
 
-    fn atom_to_string(&self, atom: JSAtom) -> Result<JSValueRef<'a>> {
+    pub(crate) fn atom_to_string(&self, atom: JSAtom) -> Result<JSValueRef<'a>> {
         let raw = unsafe { JS_AtomToString(self.context.inner, atom) };
         JSValueRef::new(self.context, raw)
     }
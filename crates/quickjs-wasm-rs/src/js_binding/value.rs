@@ -6,17 +6,76 @@ use super::properties::Properties;
 use crate::js_value::{qjs_convert::from_qjs_value, JSValue};
 use anyhow::{anyhow, Result};
 use quickjs_wasm_sys::{
-    size_t as JS_size_t, JSValue as JSValueRaw, JS_BigIntSigned, JS_BigIntToInt64,
-    JS_BigIntToUint64, JS_Call, JS_DefinePropertyValueStr, JS_DefinePropertyValueUint32,
-    JS_EvalFunction, JS_GetArrayBuffer, JS_GetPropertyStr, JS_GetPropertyUint32, JS_IsArray,
-    JS_IsArrayBuffer_Ext, JS_IsFloat64_Ext, JS_IsFunction, JS_ToCStringLen2, JS_ToFloat64,
-    JS_PROP_C_W_E, JS_TAG_BIG_INT, JS_TAG_BOOL, JS_TAG_EXCEPTION, JS_TAG_INT, JS_TAG_NULL,
-    JS_TAG_OBJECT, JS_TAG_STRING, JS_TAG_UNDEFINED,
+    size_t as JS_size_t, JSAtom, JSValue as JSValueRaw, JS_AtomToValue, JS_BigIntSigned,
+    JS_BigIntToInt64, JS_BigIntToUint64, JS_Call, JS_CallConstructor, JS_DefinePropertyValueStr,
+    JS_DefinePropertyValueUint32, JS_DeleteProperty, JS_DupValue_Ext, JS_EvalFunction, JS_FreeAtom,
+    JS_FreeCString, JS_GetArrayBuffer, JS_GetException, JS_GetFastArray_Ext, JS_GetOwnProperty,
+    JS_GetPropertyInternal, JS_GetPropertyStr, JS_GetPropertyUint32, JS_GetTypedArrayBuffer,
+    JS_HasProperty, JS_IsArray, JS_IsArrayBuffer_Ext, JS_IsError, JS_IsFloat64_Ext, JS_IsFunction,
+    JS_IsInstanceOf, JS_JSONStringify, JS_NewAtomLen, JS_ToBool, JS_ToCStringLen2, JS_ToFloat64,
+    JS_ToString, JS_ValueToAtom, JS_PROP_C_W_E, JS_TAG_BIG_INT, JS_TAG_BOOL, JS_TAG_EXCEPTION,
+    JS_TAG_INT, JS_TAG_NULL, JS_TAG_OBJECT, JS_TAG_STRING, JS_TAG_SYMBOL, JS_TAG_UNDEFINED,
 };
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::ffi::CString;
+use std::io::Read;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
 use std::str;
 
+/// Built-ins whose state can't be copied by [`JSValueRef::structured_clone`].
+const UNCLONEABLE: [&str; 3] = ["WeakMap", "WeakSet", "Promise"];
+
+/// How [`JSValueRef::structured_clone`] fills in a copy after creating it.
+enum CloneContents {
+    Elements,
+    MapEntries,
+    SetValues,
+    Properties,
+    None,
+}
+
+/// 64-bit FNV-1a, used by [`JSValueRef::content_hash`] since its output doesn't depend on the
+/// Rust version or platform.
+struct ContentHasher(u64);
+
+impl ContentHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    /// Writes the length of variable-length content before it, so that adjacent values can't be
+    /// confused, like `["ab", "c"]` and `["a", "bc"]`.
+    fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+}
+
+/// Reader returned by [`JSValueRef::as_str_reader`], which frees the C string it reads from once
+/// it's dropped.
+struct StrReader<'a> {
+    context: &'a JSContextRef,
+    ptr: *const c_char,
+    /// The part of the C string that hasn't been read yet.
+    remaining: &'a [u8],
+}
+
+impl Read for StrReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.remaining.read(buf)
+    }
+}
+
+impl Drop for StrReader<'_> {
+    fn drop(&mut self) {
+        unsafe { JS_FreeCString(self.context.inner, self.ptr) };
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum BigInt {
     Signed(i64),
@@ -85,6 +144,20 @@ impl<'a> JSValueRef<'a> {
         Self::new(self.context, return_val)
     }
 
+    /// Calls the value as a constructor with `args`, like `new` in JavaScript.
+    fn construct(&self, args: &[Self]) -> Result<Self> {
+        let mut args: Vec<JSValueRaw> = args.iter().map(|v| v.value).collect();
+        let raw = unsafe {
+            JS_CallConstructor(
+                self.context.inner,
+                self.value,
+                args.len() as i32,
+                args.as_mut_ptr(),
+            )
+        };
+        Self::new(self.context, raw)
+    }
+
     /// Converts the JavaScript value to an `i32` without checking its type.
     pub fn as_i32_unchecked(&self) -> i32 {
         self.value as i32
@@ -143,6 +216,20 @@ impl<'a> JSValueRef<'a> {
         self.is_repr_as_f64() || self.is_repr_as_i32()
     }
 
+    /// Checks if the JavaScript value is a number without a fractional part.
+    ///
+    /// Unlike [`JSValueRef::is_repr_as_i32`] this includes integral numbers outside of the `i32`
+    /// range, like `2 ** 53`, which QuickJS stores as `f64`. `NaN` and infinities aren't integers.
+    pub fn is_integer(&self) -> bool {
+        if self.is_repr_as_i32() {
+            return true;
+        }
+        self.is_repr_as_f64() && {
+            let val = self.as_f64_unchecked();
+            val.is_finite() && val.fract() == 0.0
+        }
+    }
+
     /// Checks if the JavaScript value is a `BigInt`.
     ///
     /// Returns `true` if the value is a `BigInt`, otherwise returns `false`.
@@ -151,6 +238,8 @@ impl<'a> JSValueRef<'a> {
     }
 
     /// Converts the JavaScript value to an `f64` if it is a number, otherwise returns an error.
+    ///
+    /// Same as [`JSValueRef::as_f64_strict`].
     pub fn as_f64(&self) -> Result<f64> {
         if self.is_repr_as_f64() {
             return Ok(self.as_f64_unchecked());
@@ -161,6 +250,24 @@ impl<'a> JSValueRef<'a> {
         anyhow::bail!("Value is not a number")
     }
 
+    /// Converts the JavaScript value to an `f64` if it is a number, otherwise returns an error.
+    ///
+    /// Nothing is coerced, so the string `"42"`, `true` and `BigInt`s are errors. Use
+    /// [`JSValueRef::as_f64_lossy`] to convert them the way JavaScript does.
+    pub fn as_f64_strict(&self) -> Result<f64> {
+        self.as_f64()
+    }
+
+    /// Converts the JavaScript value to an `f64` with JavaScript's `ToNumber` coercion, like
+    /// `Number(value)`.
+    ///
+    /// The string `"42"` becomes `42`, `true` becomes `1` and strings that aren't numbers become
+    /// `NaN`, so only symbols and exceptions thrown while converting an object are errors. Same
+    /// as [`JSValueRef::coerce_to_number`].
+    pub fn as_f64_lossy(&self) -> Result<f64> {
+        self.coerce_to_number()
+    }
+
     /// Tries to convert the JavaScript value to an `i32` if it is an integer, otherwise returns an error.
     pub fn try_as_integer(&self) -> Result<i32> {
         if self.is_repr_as_f64() {
@@ -176,6 +283,31 @@ impl<'a> JSValueRef<'a> {
         anyhow::bail!("Value is not a number")
     }
 
+    /// Converts the JavaScript value to an `f64` if it is a number, otherwise returns `None`.
+    ///
+    /// This is [`JSValueRef::as_f64`] for code that branches on the value's type instead of
+    /// propagating an error.
+    pub fn try_as_f64(&self) -> Option<f64> {
+        self.as_f64().ok()
+    }
+
+    /// Converts the JavaScript value to an `i32` if it is a number without a fractional part in
+    /// the `i32` range, otherwise returns `None`.
+    pub fn try_as_i32(&self) -> Option<i32> {
+        self.try_as_integer().ok()
+    }
+
+    /// Converts the JavaScript value to a string if it is a string, otherwise returns `None`.
+    ///
+    /// Strings that aren't valid UTF-8, like ones containing a lone surrogate, also return `None`.
+    pub fn try_as_str(&self) -> Option<&str> {
+        if self.is_str() {
+            self.as_str().ok()
+        } else {
+            None
+        }
+    }
+
     /// Converts the JavaScript value to a `bool` if it is a boolean, otherwise returns an error.
     pub fn as_bool(&self) -> Result<bool> {
         if self.is_bool() {
@@ -185,12 +317,103 @@ impl<'a> JSValueRef<'a> {
         }
     }
 
+    /// Converts the JavaScript value to a `bool` following JavaScript's truthiness rules.
+    ///
+    /// Unlike [`JSValueRef::as_bool`] this never fails for non-boolean values, for example `0`,
+    /// `""` and `null` are `false` while `1`, `"false"` and `{}` are `true`.
+    pub fn coerce_to_bool(&self) -> Result<bool> {
+        match unsafe { JS_ToBool(self.context.inner, self.value) } {
+            ret if ret < 0 => Err(self.as_exception()?.into_error()),
+            ret => Ok(ret > 0),
+        }
+    }
+
+    /// Converts the JavaScript value to a number following JavaScript's `ToNumber` rules.
+    ///
+    /// Unlike [`JSValueRef::as_f64`] this accepts any value, for example `"42"` is `42`, `null` is
+    /// `0` and `"abc"` is `NaN`. Objects are converted through their `valueOf` or `toString`
+    /// methods, whose exceptions are returned as errors. Symbols can't be converted.
+    pub fn coerce_to_number(&self) -> Result<f64> {
+        let mut ret = 0_f64;
+        match unsafe { JS_ToFloat64(self.context.inner, &mut ret, self.value) } {
+            ret if ret < 0 => Err(self.as_exception()?.into_error()),
+            _ => Ok(ret),
+        }
+    }
+
+    /// Converts the JavaScript value to a string following JavaScript's `ToString` rules.
+    ///
+    /// Unlike [`JSValueRef::as_str`] this accepts any value, for example `1.5` is `"1.5"`, `[1, 2]`
+    /// is `"1,2"` and `{}` is `"[object Object]"`. Objects are converted through their `toString`
+    /// or `valueOf` methods, whose exceptions are returned as errors. Symbols can't be converted.
+    /// Lone surrogates are replaced like in [`JSValueRef::as_str_lossy`].
+    pub fn coerce_to_string(&self) -> Result<String> {
+        let string = Self::new(self.context, unsafe {
+            JS_ToString(self.context.inner, self.value)
+        })?;
+        Ok(string.as_str_lossy().into_owned())
+    }
+
     /// Converts the JavaScript value to a string if it is a string.
     pub fn as_str(&self) -> Result<&str> {
         let buffer = self.as_wtf8_str_buffer();
         str::from_utf8(buffer).map_err(Into::into)
     }
 
+    /// Returns a reader over the UTF-8 bytes of the JavaScript string.
+    ///
+    /// The reader reads from the C string QuickJS returns for the string instead of copying it
+    /// again into a `String`, so large strings, like the output of `JSON.stringify`, can be
+    /// streamed to a writer with [`std::io::copy`]. QuickJS only shares the string's own buffer
+    /// for ASCII strings and encodes any other string to a new UTF-8 buffer first. Either way the
+    /// C string is released when the reader is dropped.
+    /// Returns an error if the value is not a string or isn't valid UTF-8.
+    pub fn as_str_reader(&self) -> Result<impl Read + '_> {
+        if !self.is_str() {
+            anyhow::bail!("Can't represent {:?} as a string", self.value);
+        }
+        let mut len: JS_size_t = 0;
+        let ptr = unsafe { JS_ToCStringLen2(self.context.inner, &mut len, self.value, 0) };
+        let reader = StrReader {
+            context: self.context,
+            ptr,
+            remaining: unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) },
+        };
+        str::from_utf8(reader.remaining)?;
+        Ok(reader)
+    }
+
+    /// Returns the length of the JavaScript string in UTF-16 code units, which is what its
+    /// `length` property reports.
+    ///
+    /// This differs from the byte length of [`JSValueRef::as_str`] for any non-ASCII text, for
+    /// example `"é"` has a length of 1 but takes 2 bytes, and `"😀"` has a length of 2 but takes 4
+    /// bytes.
+    pub fn utf16_len(&self) -> Result<usize> {
+        if !self.is_str() {
+            anyhow::bail!("Can't get the UTF-16 length of {:?}", self.value);
+        }
+        Ok(self.get_property("length")?.as_u32_unchecked() as usize)
+    }
+
+    /// Returns the longest prefix of the string that is at most `max_len` UTF-16 code units
+    /// long.
+    ///
+    /// The string is only cut between code points, so a character outside of the Basic
+    /// Multilingual Plane is either kept whole or dropped. Combining characters are code points
+    /// of their own and can be cut off from the character they modify.
+    pub fn truncated_str(&self, max_len: usize) -> Result<&str> {
+        let string = self.as_str()?;
+        let mut len = 0;
+        for (index, c) in string.char_indices() {
+            len += c.len_utf16();
+            if len > max_len {
+                return Ok(&string[..index]);
+            }
+        }
+        Ok(string)
+    }
+
     /// Converts the JavaScript value to a string, replacing any invalid UTF-8 sequences with the
     /// Unicode replacement character (U+FFFD).
     pub fn as_str_lossy(&self) -> std::borrow::Cow<str> {
@@ -248,32 +471,83 @@ impl<'a> JSValueRef<'a> {
         }
     }
 
-    /// Converts the JavaScript value to a byte slice if it is an ArrayBuffer, otherwise returns an error.
+    /// Converts the JavaScript value to a byte slice if it is an ArrayBuffer or a typed array,
+    /// otherwise returns an error.
+    ///
+    /// For a typed array, like a `Uint8Array` created with `subarray`, the slice only covers the
+    /// bytes the array is a view of, starting at its `byteOffset` and `byteLength` bytes long.
     pub fn as_bytes(&self) -> Result<&[u8]> {
-        let mut len = 0;
-        let ptr = unsafe { JS_GetArrayBuffer(self.context.inner, &mut len, self.value) };
-        if ptr.is_null() {
-            Err(anyhow!(
-                "Can't represent {:?} as an array buffer",
-                self.value
-            ))
-        } else {
-            Ok(unsafe { std::slice::from_raw_parts(ptr, len as _) })
-        }
+        let (ptr, len) = self.byte_range()?;
+        Ok(unsafe { std::slice::from_raw_parts(ptr, len) })
     }
 
-    /// Converts the JavaScript value to a mutable byte slice if it is an ArrayBuffer, otherwise returns an error.
+    /// Converts the JavaScript value to a mutable byte slice if it is an ArrayBuffer or a typed
+    /// array, otherwise returns an error.
+    ///
+    /// Like [`JSValueRef::as_bytes`], the slice of a typed array only covers the bytes it's a view
+    /// of.
     pub fn as_bytes_mut(&self) -> Result<&mut [u8]> {
+        let (ptr, len) = self.byte_range()?;
+        Ok(unsafe { std::slice::from_raw_parts_mut(ptr, len) })
+    }
+
+    /// Calls `f` with the bytes of an ArrayBuffer or a typed array, without copying them, and
+    /// returns its result. Returns an error if the value is neither.
+    ///
+    /// The slice is only borrowed for the duration of `f`, so it can't outlive the buffer. `f`
+    /// shouldn't run JavaScript that could detach or resize the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// let buffer = context.eval_global("test.js", "new Uint8Array([1, 2, 3]).buffer")?;
+    /// let first = buffer.with_array_buffer_bytes(|bytes| bytes[0])?;
+    /// assert_eq!(1, first);
+    /// ```
+    pub fn with_array_buffer_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<R> {
+        let (ptr, len) = self.byte_range()?;
+        Ok(f(unsafe { std::slice::from_raw_parts(ptr, len) }))
+    }
+
+    /// Returns a pointer to the bytes of an ArrayBuffer or of a typed array's view of its buffer,
+    /// and their length.
+    fn byte_range(&self) -> Result<(*mut u8, usize)> {
         let mut len = 0;
         let ptr = unsafe { JS_GetArrayBuffer(self.context.inner, &mut len, self.value) };
-        if ptr.is_null() {
-            Err(anyhow!(
-                "Can't represent {:?} as an array buffer",
-                self.value
-            ))
-        } else {
-            Ok(unsafe { std::slice::from_raw_parts_mut(ptr, len as _) })
+        if !ptr.is_null() {
+            return Ok((ptr, len as _));
+        }
+        // `JS_GetArrayBuffer` and `JS_GetTypedArrayBuffer` throw a `TypeError` for other values,
+        // which is discarded since an error is returned instead.
+        unsafe { JS_GetException(self.context.inner) };
+
+        if self.is_object() {
+            let (mut offset, mut length, mut bytes_per_element) = (0, 0, 0);
+            let buffer = unsafe {
+                JS_GetTypedArrayBuffer(
+                    self.context.inner,
+                    self.value,
+                    &mut offset,
+                    &mut length,
+                    &mut bytes_per_element,
+                )
+            };
+            if JSValueRef::new_unchecked(self.context, buffer).is_exception() {
+                unsafe { JS_GetException(self.context.inner) };
+            } else {
+                let ptr = unsafe { JS_GetArrayBuffer(self.context.inner, &mut len, buffer) };
+                if !ptr.is_null() {
+                    return Ok((unsafe { ptr.add(offset as _) }, length as _));
+                }
+                unsafe { JS_GetException(self.context.inner) };
+            }
         }
+
+        Err(anyhow!(
+            "Can't represent {:?} as an array buffer",
+            self.value
+        ))
     }
 
     /// Retrieves the properties of the JavaScript value.
@@ -281,11 +555,203 @@ impl<'a> JSValueRef<'a> {
         Properties::new(self.context, self.value)
     }
 
+    /// Calls `f` with the key and value of each of the object's own enumerable properties.
+    ///
+    /// Iteration stops at the first error returned by `f`, which is then returned by this method.
+    pub fn for_each_entry<F>(&self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&str, JSValueRef<'a>) -> Result<()>,
+    {
+        let mut properties = self.properties()?;
+        while let Some(key) = properties.next_key()? {
+            let value = properties.next_value()?;
+            f(key.as_str()?, value)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the keys of the object's own enumerable properties, in property order.
+    ///
+    /// Keys are returned exactly as they're defined in JavaScript, without reading the values.
+    /// Array indices are included as strings and symbol keys are left out.
+    pub fn own_keys(&self) -> Result<Vec<String>> {
+        let mut properties = self.properties()?;
+        let mut keys = Vec::new();
+        while let Some(atom) = properties.next_atom() {
+            let key = Self::new(self.context, unsafe {
+                JS_AtomToValue(self.context.inner, atom)
+            })?;
+            if !key.is_symbol() {
+                keys.push(key.as_str()?.to_string());
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Converts the value to JSON text with `JSON.stringify`.
+    ///
+    /// Fails if `JSON.stringify` throws, for example for circular references or `BigInt`s, or if
+    /// it returns `undefined`, which it does for functions, symbols and `undefined`.
+    pub fn to_json_string(&self) -> Result<String> {
+        Ok(self.stringify()?.as_str()?.to_string())
+    }
+
+    /// Same as [`JSValueRef::to_json_string`] but returns the bytes of the JSON text as produced by
+    /// QuickJS, without checking that they're valid UTF-8.
+    ///
+    /// This is meant for writing JSON to a byte sink, like stdout. `JSON.stringify` escapes lone
+    /// surrogates, so the bytes are valid UTF-8.
+    pub fn to_json_bytes(&self) -> Result<Vec<u8>> {
+        Ok(self.stringify()?.as_wtf8_str_buffer().to_vec())
+    }
+
+    fn stringify(&self) -> Result<Self> {
+        let undefined = self.context.undefined_value()?;
+        let raw = unsafe {
+            JS_JSONStringify(
+                self.context.inner,
+                self.value,
+                undefined.value,
+                undefined.value,
+            )
+        };
+        let json = Self::new(self.context, raw)?;
+        if json.is_undefined() {
+            anyhow::bail!("Value can't be represented as JSON");
+        }
+        Ok(json)
+    }
+
+    /// Renders the value as bounded, JSON-like text meant for error messages and logs.
+    ///
+    /// Unlike `JSON.stringify` this works on any value: functions render as `"[Function]"`,
+    /// symbols as `"[Symbol]"`, errors as `"[Name: message]"` and `undefined`, `NaN` and `BigInt`s
+    /// as they'd be written in JavaScript. Objects and arrays nested more than `max_depth` levels
+    /// deep render as `"[Object]"` and `"[Array]"`, and the text is cut to at most `max_len`
+    /// characters, ending with `...` when it was truncated.
+    pub fn to_debug_json(&self, max_depth: usize, max_len: usize) -> String {
+        let mut out = String::new();
+        self.write_debug_json(&mut out, max_depth, max_len);
+        if out.chars().count() <= max_len {
+            return out;
+        }
+        let mut truncated: String = out.chars().take(max_len.saturating_sub(3)).collect();
+        truncated.push_str("...");
+        truncated
+    }
+
+    fn write_debug_json(&self, out: &mut String, depth: usize, max_len: usize) {
+        const UNREADABLE: &str = "\"[Unreadable]\"";
+
+        if self.is_function() {
+            out.push_str("\"[Function]\"");
+        } else if self.is_symbol() {
+            out.push_str("\"[Symbol]\"");
+        } else if self.is_undefined() {
+            out.push_str("undefined");
+        } else if self.is_null() {
+            out.push_str("null");
+        } else if self.is_bool() {
+            match self.as_bool() {
+                Ok(val) => out.push_str(&val.to_string()),
+                Err(_) => out.push_str(UNREADABLE),
+            }
+        } else if self.is_repr_as_i32() {
+            out.push_str(&self.as_i32_unchecked().to_string());
+        } else if self.is_repr_as_f64() {
+            out.push_str(&JSValue::Float(self.as_f64_unchecked()).to_string());
+        } else if self.is_big_int() {
+            match self.as_big_int_unchecked() {
+                Ok(BigInt::Signed(v)) => out.push_str(&format!("{v}n")),
+                Ok(BigInt::Unsigned(v)) => out.push_str(&format!("{v}n")),
+                Err(_) => out.push_str(UNREADABLE),
+            }
+        } else if self.is_str() {
+            push_json_string(out, &self.as_str_lossy());
+        } else if self.is_array_buffer() {
+            out.push_str("\"[ArrayBuffer]\"");
+        } else if self.is_error() {
+            let name = self
+                .get_property("name")
+                .map(|v| v.as_str_lossy().into_owned());
+            let message = self
+                .get_property("message")
+                .map(|v| v.as_str_lossy().into_owned());
+            match (name, message) {
+                (Ok(name), Ok(message)) => push_json_string(out, &format!("[{name}: {message}]")),
+                _ => out.push_str(UNREADABLE),
+            }
+        } else if self.is_array() {
+            if depth == 0 {
+                out.push_str("\"[Array]\"");
+                return;
+            }
+            let Ok(length) = self.get_property("length") else {
+                out.push_str(UNREADABLE);
+                return;
+            };
+            out.push('[');
+            for index in 0..length.as_u32_unchecked() {
+                if out.len() > max_len {
+                    break;
+                }
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                match self.get_indexed_property(index) {
+                    Ok(element) => element.write_debug_json(out, depth - 1, max_len),
+                    Err(_) => out.push_str(UNREADABLE),
+                }
+            }
+            out.push(']');
+        } else if self.is_object() {
+            if depth == 0 {
+                out.push_str("\"[Object]\"");
+                return;
+            }
+            let Ok(mut properties) = self.properties() else {
+                out.push_str(UNREADABLE);
+                return;
+            };
+            out.push('{');
+            let mut first = true;
+            while let Ok(Some(key)) = properties.next_key() {
+                if out.len() > max_len {
+                    break;
+                }
+                if !first {
+                    out.push_str(", ");
+                }
+                first = false;
+                push_json_string(out, &key.as_str_lossy());
+                out.push_str(": ");
+                match properties.next_value() {
+                    Ok(value) => value.write_debug_json(out, depth - 1, max_len),
+                    Err(_) => out.push_str(UNREADABLE),
+                }
+            }
+            out.push('}');
+        } else {
+            out.push_str("\"[Unknown]\"");
+        }
+    }
+
     /// Checks if the JavaScript value is represented as an `f64`.
     pub fn is_repr_as_f64(&self) -> bool {
         unsafe { JS_IsFloat64_Ext(self.get_tag()) == 1 }
     }
 
+    /// Checks if the JavaScript value is `-0`.
+    ///
+    /// `-0 === 0` in JavaScript, but `Object.is(-0, 0)` is `false` and the two have different bit
+    /// patterns as an `f64`.
+    pub fn is_negative_zero(&self) -> bool {
+        self.is_repr_as_f64() && {
+            let v = self.as_f64_unchecked();
+            v == 0.0 && v.is_sign_negative()
+        }
+    }
+
     /// Checks if the JavaScript value is represented as an `i32`.
     pub fn is_repr_as_i32(&self) -> bool {
         self.get_tag() == JS_TAG_INT
@@ -301,6 +767,11 @@ impl<'a> JSValueRef<'a> {
         self.get_tag() == JS_TAG_BOOL
     }
 
+    /// Checks if the JavaScript value is a symbol.
+    pub fn is_symbol(&self) -> bool {
+        self.get_tag() == JS_TAG_SYMBOL
+    }
+
     /// Checks if the JavaScript value is an array.
     pub fn is_array(&self) -> bool {
         unsafe { JS_IsArray(self.context.inner, self.value) == 1 }
@@ -331,11 +802,27 @@ impl<'a> JSValueRef<'a> {
         self.is_null() | self.is_undefined()
     }
 
-    /// Checks if the JavaScript value is a function.
+    /// Checks if the JavaScript value can be called, like `typeof value === "function"`.
+    ///
+    /// Besides functions, this includes proxies whose target is callable, whether or not they
+    /// have an `apply` trap. A proxy of a plain object isn't callable even if it has an `apply`
+    /// trap, since the trap is never consulted.
     pub fn is_function(&self) -> bool {
         unsafe { JS_IsFunction(self.context.inner, self.value) != 0 }
     }
 
+    /// Checks if the JavaScript value can be called with [`JSValueRef::call`].
+    ///
+    /// The same as [`JSValueRef::is_function`], under the name the ECMAScript specification uses.
+    pub fn is_callable(&self) -> bool {
+        self.is_function()
+    }
+
+    /// Checks if the JavaScript value is an `Error`, including subclasses like `TypeError`.
+    pub fn is_error(&self) -> bool {
+        unsafe { JS_IsError(self.context.inner, self.value) != 0 }
+    }
+
     /// Retrieves the value of a property with the specified `key` from the JavaScript object.
     pub fn get_property(&self, key: impl Into<Vec<u8>>) -> Result<Self> {
         let cstring_key = CString::new(key)?;
@@ -364,22 +851,581 @@ impl<'a> JSValueRef<'a> {
         Ok(())
     }
 
+    /// Freezes the JavaScript object with `Object.freeze`, so its properties can no longer be added,
+    /// removed or changed.
+    ///
+    /// Freezing is shallow, objects stored in the properties stay mutable. Freezing values that
+    /// aren't objects has no effect.
+    pub fn freeze(&self) -> Result<()> {
+        let object = self.context.global_object()?.get_property("Object")?;
+        object.get_property("freeze")?.call(&object, &[*self])?;
+        Ok(())
+    }
+
+    /// Checks if the JavaScript value is frozen, as reported by `Object.isFrozen`.
+    ///
+    /// Values that aren't objects are always frozen.
+    pub fn is_frozen(&self) -> Result<bool> {
+        let object = self.context.global_object()?.get_property("Object")?;
+        object
+            .get_property("isFrozen")?
+            .call(&object, &[*self])?
+            .as_bool()
+    }
+
+    /// Collects the string-keyed properties of the object and of the objects in its prototype
+    /// chain, for introspection.
+    ///
+    /// Own properties come first, followed by the properties of each prototype in turn. Names
+    /// that are shadowed by an object closer in the chain are only listed once, with the value
+    /// read from this object. Non-enumerable properties like class methods are included, but the
+    /// walk stops at `Object.prototype` so the built-ins every object inherits are left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// let val = context.eval_global("test.js", "class A { greet() {} }; new A()")?;
+    /// let names: Vec<String> = val
+    ///     .prototype_chain_properties()?
+    ///     .into_iter()
+    ///     .map(|(name, _)| name)
+    ///     .collect();
+    /// assert_eq!(["constructor", "greet"], names.as_slice());
+    /// ```
+    pub fn prototype_chain_properties(&self) -> Result<Vec<(String, Self)>> {
+        if !self.is_object() {
+            anyhow::bail!("Can't list the properties of {:?}", self.value);
+        }
+
+        let object = self.context.global_object()?.get_property("Object")?;
+        let get_prototype_of = object.get_property("getPrototypeOf")?;
+        let get_own_property_names = object.get_property("getOwnPropertyNames")?;
+        let object_prototype = object.get_property("prototype")?;
+
+        let mut seen = HashSet::new();
+        let mut properties = Vec::new();
+        let mut current = *self;
+        while !current.is_null() && current.value != object_prototype.value {
+            let names = get_own_property_names.call(&object, &[current])?;
+            for name in names.as_array_vec()? {
+                let name = name.as_str()?;
+                if seen.insert(name.to_string()) {
+                    properties.push((name.to_string(), self.get_property(name)?));
+                }
+            }
+            current = get_prototype_of.call(&object, &[current])?;
+        }
+        Ok(properties)
+    }
+
+    /// Computes a hash of the value's structure and contents, for deduplicating or caching values
+    /// by content.
+    ///
+    /// Values that are equal as data hash the same: object keys are sorted before hashing, so
+    /// `{a: 1, b: 2}` and `{b: 2, a: 1}` hash equally, numbers are hashed by value, so `1` and
+    /// `1.0` are the same, and all `NaN`s hash the same as do `0` and `-0`. Only own enumerable
+    /// string-keyed properties are hashed, like with `JSON.stringify`. `Date`s are hashed by their
+    /// time value, and `Map` entries and `Set` values are hashed in insertion order. The hash is
+    /// stable across runs and platforms. Functions, symbols and circular references return an
+    /// error.
+    pub fn content_hash(&self) -> Result<u64> {
+        let mut hasher = ContentHasher(0xcbf29ce484222325);
+        self.hash_content(&mut hasher, &mut Vec::new())?;
+        Ok(hasher.0)
+    }
+
+    fn hash_content(
+        &self,
+        hasher: &mut ContentHasher,
+        ancestors: &mut Vec<JSValueRaw>,
+    ) -> Result<()> {
+        if self.is_undefined() {
+            hasher.write(&[0]);
+        } else if self.is_null() {
+            hasher.write(&[1]);
+        } else if self.is_bool() {
+            hasher.write(&[2, self.as_bool()? as u8]);
+        } else if self.is_number() {
+            let v = self.as_f64()?;
+            let v = if v.is_nan() {
+                f64::NAN
+            } else if v == 0.0 {
+                0.0
+            } else {
+                v
+            };
+            hasher.write(&[3]);
+            hasher.write(&v.to_bits().to_le_bytes());
+        } else if self.is_big_int() {
+            let digits = self.coerce_to_string()?;
+            hasher.write(&[4]);
+            hasher.write_len(digits.len());
+            hasher.write(digits.as_bytes());
+        } else if self.is_str() {
+            let v = self.as_str_lossy();
+            hasher.write(&[5]);
+            hasher.write_len(v.len());
+            hasher.write(v.as_bytes());
+        } else if self.is_function() {
+            anyhow::bail!("Functions can't be hashed");
+        } else if self.is_symbol() {
+            anyhow::bail!("Symbols can't be hashed");
+        } else if self.is_object() {
+            if ancestors.contains(&self.value) {
+                anyhow::bail!("Can't hash a circular reference");
+            }
+            ancestors.push(self.value);
+            if self.is_array_buffer() {
+                let bytes = self.as_bytes()?;
+                hasher.write(&[6]);
+                hasher.write_len(bytes.len());
+                hasher.write(bytes);
+            } else if self.is_array() {
+                let elements = self.as_array_vec()?;
+                hasher.write(&[7]);
+                hasher.write_len(elements.len());
+                for element in elements {
+                    element.hash_content(hasher, ancestors)?;
+                }
+            } else if self.is_instance_of_global("Date")? {
+                let time = self.get_property("getTime")?.call(self, &[])?;
+                hasher.write(&[9]);
+                time.hash_content(hasher, ancestors)?;
+            } else if self.is_instance_of_global("Map")? || self.is_instance_of_global("Set")? {
+                let is_map = self.is_instance_of_global("Map")?;
+                let entries = self
+                    .context
+                    .global_object()?
+                    .get_property("Array")?
+                    .get_property("from")?
+                    .call(self, &[*self])?
+                    .as_array_vec()?;
+                hasher.write(&[if is_map { 10 } else { 11 }]);
+                hasher.write_len(entries.len());
+                for entry in entries {
+                    if is_map {
+                        entry
+                            .get_indexed_property(0)?
+                            .hash_content(hasher, ancestors)?;
+                        entry
+                            .get_indexed_property(1)?
+                            .hash_content(hasher, ancestors)?;
+                    } else {
+                        entry.hash_content(hasher, ancestors)?;
+                    }
+                }
+            } else {
+                let mut entries = Vec::new();
+                let mut properties = self.properties()?;
+                while let Some(key) = properties.next_key()? {
+                    if key.is_str() {
+                        entries.push((key.as_str_lossy().into_owned(), properties.next_value()?));
+                    }
+                }
+                entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                hasher.write(&[8]);
+                hasher.write_len(entries.len());
+                for (key, value) in entries {
+                    hasher.write_len(key.len());
+                    hasher.write(key.as_bytes());
+                    value.hash_content(hasher, ancestors)?;
+                }
+            }
+            ancestors.pop();
+        } else {
+            anyhow::bail!("Can't hash {:?}", self.value);
+        }
+        Ok(())
+    }
+
+    /// Makes a deep copy of the value in the same context, following the structured clone
+    /// algorithm used by `structuredClone` in browsers.
+    ///
+    /// Arrays, `ArrayBuffer`s, typed arrays, `DataView`s, `Map`s, `Set`s, `Date`s and `RegExp`s
+    /// are copied with their contents. Other objects are copied as plain objects with their own
+    /// enumerable string-keyed properties, so their prototype is not preserved. Objects
+    /// referenced more than once, including through cycles, are copied once and the copy is
+    /// referenced in the same places. Functions, symbols, `WeakMap`s, `WeakSet`s and `Promise`s
+    /// can't be cloned and return an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let context = JSContextRef::default();
+    /// let original = context.eval_global("test.js", "({items: [1, 2]})")?;
+    /// let copy = original.structured_clone()?;
+    /// original.get_property("items")?.append_property(context.value_from_i32(3)?)?;
+    /// assert_eq!(2, copy.get_property("items")?.as_array_vec()?.len());
+    /// ```
+    pub fn structured_clone(&self) -> Result<Self> {
+        // Copies are kept in an array and read back from it whenever they are stored, since
+        // defining a property takes over the reference passed to it.
+        let copies = self.context.array_value()?;
+        self.clone_with(&mut HashMap::new(), &copies)
+    }
+
+    fn clone_with(&self, seen: &mut HashMap<JSValueRaw, u32>, copies: &Self) -> Result<Self> {
+        if self.is_function() {
+            anyhow::bail!("Functions can't be cloned");
+        }
+        if self.is_symbol() {
+            anyhow::bail!("Symbols can't be cloned");
+        }
+        if !self.is_object() {
+            return Ok(*self);
+        }
+        if let Some(index) = seen.get(&self.value) {
+            return copies.get_indexed_property(*index);
+        }
+
+        let global = self.context.global_object()?;
+        let (copy, contents) = if self.is_array() {
+            (self.context.array_value()?, CloneContents::Elements)
+        } else if self.is_array_buffer() {
+            let copy = self.context.array_buffer_value(self.as_bytes()?)?;
+            (copy, CloneContents::None)
+        } else if global
+            .get_property("ArrayBuffer")?
+            .get_property("isView")?
+            .call(self, &[*self])?
+            .as_bool()?
+        {
+            let buffer = self.get_property("buffer")?.clone_with(seen, copies)?;
+            let length = if self.is_instance_of_global("DataView")? {
+                self.get_property("byteLength")?
+            } else {
+                self.get_property("length")?
+            };
+            let args = [buffer, self.get_property("byteOffset")?, length];
+            let copy = self.get_property("constructor")?.construct(&args)?;
+            (copy, CloneContents::None)
+        } else if self.is_instance_of_global("Date")? {
+            let time = self.get_property("getTime")?.call(self, &[])?;
+            let copy = global.get_property("Date")?.construct(&[time])?;
+            (copy, CloneContents::None)
+        } else if self.is_instance_of_global("RegExp")? {
+            let copy = global.get_property("RegExp")?.construct(&[*self])?;
+            (copy, CloneContents::None)
+        } else if self.is_instance_of_global("Map")? {
+            let copy = global.get_property("Map")?.construct(&[])?;
+            (copy, CloneContents::MapEntries)
+        } else if self.is_instance_of_global("Set")? {
+            let copy = global.get_property("Set")?.construct(&[])?;
+            (copy, CloneContents::SetValues)
+        } else {
+            for name in UNCLONEABLE {
+                if self.is_instance_of_global(name)? {
+                    anyhow::bail!("{name} can't be cloned");
+                }
+            }
+            (self.context.object_value()?, CloneContents::Properties)
+        };
+
+        let index = seen.len() as u32;
+        copies.set_indexed_property(index, copy)?;
+        seen.insert(self.value, index);
+        let copy = copies.get_indexed_property(index)?;
+
+        match contents {
+            CloneContents::Elements => {
+                let length = self.get_property("length")?.as_u32_unchecked();
+                for i in 0..length {
+                    let element = self.get_indexed_property(i)?.clone_with(seen, copies)?;
+                    copy.set_indexed_property(i, element)?;
+                }
+            }
+            CloneContents::MapEntries | CloneContents::SetValues => {
+                let is_map = matches!(contents, CloneContents::MapEntries);
+                let method = copy.get_property(if is_map { "set" } else { "add" })?;
+                let entries = global
+                    .get_property("Array")?
+                    .get_property("from")?
+                    .call(self, &[*self])?;
+                for entry in entries.as_array_vec()? {
+                    let args = if is_map {
+                        vec![
+                            entry.get_indexed_property(0)?.clone_with(seen, copies)?,
+                            entry.get_indexed_property(1)?.clone_with(seen, copies)?,
+                        ]
+                    } else {
+                        vec![entry.clone_with(seen, copies)?]
+                    };
+                    method.call(&copy, &args)?;
+                }
+            }
+            CloneContents::Properties => {
+                let mut properties = self.properties()?;
+                while let Some(key) = properties.next_key()? {
+                    if !key.is_str() {
+                        continue;
+                    }
+                    let value = properties.next_value()?.clone_with(seen, copies)?;
+                    copy.set_property(key.as_str()?, value)?;
+                }
+            }
+            CloneContents::None => {}
+        }
+        Ok(copy)
+    }
+
+    /// Checks if the JavaScript object has an own property named `key`.
+    ///
+    /// Unlike checking whether [`JSValueRef::get_property`] returns `undefined`, this tells an
+    /// absent property apart from one set to `undefined`. Inherited properties aren't own
+    /// properties, see [`JSValueRef::has_property`] to include them.
+    pub fn has_own_property(&self, key: &str) -> Result<bool> {
+        self.with_atom(key, |atom| unsafe {
+            JS_GetOwnProperty(self.context.inner, ptr::null_mut(), self.value, atom)
+        })
+    }
+
+    /// Checks if the JavaScript object or its prototype chain has a property named `key`, like
+    /// JavaScript's `in` operator.
+    ///
+    /// Returns an error if the value is not an object.
+    pub fn has_property(&self, key: &str) -> Result<bool> {
+        self.with_atom(key, |atom| unsafe {
+            JS_HasProperty(self.context.inner, self.value, atom)
+        })
+    }
+
+    /// Deletes the own property named `key` from the JavaScript object, like JavaScript's
+    /// `delete` operator.
+    ///
+    /// Returns `true` if the property is gone, which includes when it didn't exist, and `false`
+    /// if it can't be deleted because it's not configurable.
+    pub fn delete_property(&self, key: &str) -> Result<bool> {
+        self.with_atom(key, |atom| unsafe {
+            JS_DeleteProperty(self.context.inner, self.value, atom, 0)
+        })
+    }
+
+    /// Calls `f` with the atom for `key`, returning an error if `f` returns a negative value,
+    /// which QuickJS uses to signal an exception.
+    fn with_atom(&self, key: &str, f: impl FnOnce(JSAtom) -> c_int) -> Result<bool> {
+        let ret = unsafe {
+            let atom = JS_NewAtomLen(
+                self.context.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as _,
+            );
+            let ret = f(atom);
+            JS_FreeAtom(self.context.inner, atom);
+            ret
+        };
+
+        if ret < 0 {
+            let exception = self.as_exception()?;
+            return Err(exception.into_error());
+        }
+        Ok(ret > 0)
+    }
+
+    /// Checks if the value is an instance of the global constructor named `constructor`, like
+    /// `WeakMap`.
+    ///
+    /// Returns `false` when the global is not defined or is not a function.
+    pub(crate) fn is_instance_of_global(&self, constructor: &str) -> Result<bool> {
+        let constructor = self.context.global_object()?.get_property(constructor)?;
+        if !constructor.is_function() {
+            return Ok(false);
+        }
+
+        let ret = unsafe { JS_IsInstanceOf(self.context.inner, self.value, constructor.value) };
+        if ret < 0 {
+            let exception = self.as_exception()?;
+            return Err(exception.into_error());
+        }
+        Ok(ret > 0)
+    }
+
+    /// Calls the value's `Symbol.iterator` method, returning the iterator or `None` if the value
+    /// isn't iterable.
+    pub(crate) fn iterator(&self) -> Result<Option<Self>> {
+        let symbol = self
+            .context
+            .global_object()?
+            .get_property("Symbol")?
+            .get_property("iterator")?;
+        if !symbol.is_symbol() {
+            return Ok(None);
+        }
+
+        let method = unsafe {
+            let atom = JS_ValueToAtom(self.context.inner, symbol.value);
+            let raw = JS_GetPropertyInternal(self.context.inner, self.value, atom, self.value, 0);
+            JS_FreeAtom(self.context.inner, atom);
+            Self::new(self.context, raw)?
+        };
+        if !method.is_function() {
+            return Ok(None);
+        }
+        method.call(self, &[]).map(Some)
+    }
+
     /// Retrieves the value of an indexed property from the JavaScript object.
     /// This is used for arrays.
+    ///
+    /// Indices past the end of an array, and holes in sparse arrays, return `undefined` instead of
+    /// an error.
     pub fn get_indexed_property(&self, index: u32) -> Result<Self> {
         let raw = unsafe { JS_GetPropertyUint32(self.context.inner, self.value, index) };
         Self::new(self.context, raw)
     }
 
-    /// Appends a property with the value `val` to the JavaScript object.
-    /// This is used for arrays.
-    pub fn append_property(&self, val: JSValueRef) -> Result<()> {
-        let len = self.get_property("length")?;
+    /// Retrieves the `len` indexed properties starting at `start`, like
+    /// [`JSValueRef::get_indexed_property`] for each index.
+    ///
+    /// The elements of arrays QuickJS stores contiguously, which are most arrays without holes,
+    /// are read straight from that storage instead of being looked up one index at a time. Other
+    /// objects, and indices past the stored elements, are looked up like with
+    /// [`JSValueRef::get_indexed_property`].
+    ///
+    /// Indices past the end of the array return `undefined`. Returns an error if the range goes
+    /// past `u32::MAX`.
+    pub fn get_indexed_range(&self, start: u32, len: u32) -> Result<Vec<Self>> {
+        let end = start
+            .checked_add(len)
+            .ok_or_else(|| anyhow!("Index range {start}+{len} is out of bounds"))?;
+        let mut elements = ptr::null_mut();
+        let mut count = 0;
+        let is_fast = unsafe {
+            JS_GetFastArray_Ext(self.context.inner, self.value, &mut elements, &mut count)
+        };
+        let fast_end = if is_fast != 0 {
+            end.min(count).max(start)
+        } else {
+            start
+        };
+
+        let mut range = Vec::with_capacity(len as usize);
+        for index in start..fast_end {
+            let raw = unsafe { JS_DupValue_Ext(self.context.inner, *elements.add(index as usize)) };
+            range.push(Self::new_unchecked(self.context, raw));
+        }
+        for index in fast_end..end {
+            range.push(self.get_indexed_property(index)?);
+        }
+        Ok(range)
+    }
+
+    /// Retrieves a nested value using a dotted path with optional array indices, for example
+    /// `db.host` or `items[0].name`.
+    ///
+    /// Missing keys anywhere along the path produce `undefined`. Use
+    /// [`JSValueRef::get_path_strict`] to get an error instead.
+    pub fn get_path(&self, path: &str) -> Result<Self> {
+        self.resolve_path(path, false)
+    }
+
+    /// Same as [`JSValueRef::get_path`] but returns an error if any part of the path is missing.
+    pub fn get_path_strict(&self, path: &str) -> Result<Self> {
+        self.resolve_path(path, true)
+    }
+
+    fn resolve_path(&self, path: &str, strict: bool) -> Result<Self> {
+        let mut current = *self;
+        for segment in parse_path(path)? {
+            if current.is_null_or_undefined() {
+                if strict {
+                    anyhow::bail!("Path `{path}` does not exist");
+                }
+                return self.context.undefined_value();
+            }
+            current = match segment {
+                PathSegment::Key(key) => current.get_property(key)?,
+                PathSegment::Index(index) => current.get_indexed_property(index)?,
+            };
+        }
+        if strict && current.is_undefined() {
+            anyhow::bail!("Path `{path}` does not exist");
+        }
+        Ok(current)
+    }
+
+    /// Retrieves a nested value using an RFC 6901 JSON Pointer, for example `/items/0/name`.
+    ///
+    /// The empty pointer `""` refers to the value itself. In each reference token `~1` stands for
+    /// `/` and `~0` for `~`, and tokens are array indices for arrays. Unlike
+    /// [`JSValueRef::get_path`], only own properties are found, and a pointer to anything that
+    /// doesn't exist, including the `-` index past the end of an array, is an error.
+    pub fn resolve_pointer(&self, pointer: &str) -> Result<Self> {
+        if pointer.is_empty() {
+            return Ok(*self);
+        }
+        let Some(tokens) = pointer.strip_prefix('/') else {
+            anyhow::bail!("Invalid JSON pointer `{pointer}`: must start with `/`");
+        };
+
+        let mut current = *self;
+        for token in tokens.split('/') {
+            let token = unescape_pointer_token(token)
+                .ok_or_else(|| anyhow!("Invalid JSON pointer `{pointer}`: invalid escape"))?;
+            let found = if current.is_array() {
+                match parse_pointer_index(&token) {
+                    Some(index) if index < current.array_length()? => {
+                        Some(current.get_indexed_property(index)?)
+                    }
+                    _ => None,
+                }
+            } else if current.is_object() && current.has_own_property(&token)? {
+                Some(current.get_property(&*token)?)
+            } else {
+                None
+            };
+            current = found.ok_or_else(|| anyhow!("JSON pointer `{pointer}` does not exist"))?;
+        }
+        Ok(current)
+    }
+
+    /// Collects the elements of the JavaScript array into a `Vec`.
+    ///
+    /// Returns an error if the value is not an array.
+    pub fn as_array_vec(&self) -> Result<Vec<Self>> {
+        if !self.is_array() {
+            anyhow::bail!("Can't represent {:?} as an array", self.value);
+        }
+        self.get_indexed_range(0, self.array_length()?)
+    }
+
+    /// Appends a property with the value `val` to the JavaScript object.
+    /// This is used for arrays.
+    pub fn append_property(&self, val: JSValueRef) -> Result<()> {
+        let len = self.get_property("length")?;
+        self.set_indexed_property(len.value as u32, val)
+    }
+
+    /// Returns the length of the JavaScript array.
+    ///
+    /// Returns an error if the value is not an array.
+    pub fn array_length(&self) -> Result<u32> {
+        if !self.is_array() {
+            anyhow::bail!("Can't represent {:?} as an array", self.value);
+        }
+        Ok(self.get_property("length")?.as_u32_unchecked())
+    }
+
+    /// Appends `val` to the end of the JavaScript array and returns the array's new length.
+    ///
+    /// Returns an error if the value is not an array.
+    pub fn push(&self, val: JSValueRef) -> Result<u32> {
+        let len = self.array_length()?;
+        self.set_indexed_property(len, val)?;
+        Ok(len + 1)
+    }
+
+    /// Defines the element at `index` of the JavaScript object to `val`.
+    ///
+    /// Unlike [`JSValueRef::append_property`] this doesn't need to look up the array's length, so
+    /// it's cheaper when the caller already tracks the next index. Setting an index past the end of
+    /// an array extends it, leaving holes that read as `undefined`.
+    pub fn set_indexed_property(&self, index: u32, val: JSValueRef) -> Result<()> {
         let ret = unsafe {
             JS_DefinePropertyValueUint32(
                 self.context.inner,
                 self.value,
-                len.value as u32,
+                index,
                 val.value,
                 JS_PROP_C_W_E as i32,
             )
@@ -413,6 +1459,74 @@ impl<'a> JSValueRef<'a> {
     }
 }
 
+enum PathSegment<'p> {
+    Key(&'p str),
+    Index(u32),
+}
+
+/// Splits a path like `items[0].name` into its keys and indices.
+fn parse_path(path: &str) -> Result<Vec<PathSegment<'_>>> {
+    let mut segments = Vec::new();
+    if path.is_empty() {
+        return Ok(segments);
+    }
+
+    for part in path.split('.') {
+        let (key, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key));
+        } else if rest.is_empty() {
+            anyhow::bail!("Invalid path `{path}`: empty segment");
+        }
+
+        while !rest.is_empty() {
+            let end = rest
+                .find(']')
+                .filter(|_| rest.starts_with('['))
+                .ok_or_else(|| anyhow!("Invalid path `{path}`: malformed index"))?;
+            let index = rest[1..end].parse().map_err(|_| {
+                anyhow!(
+                    "Invalid path `{path}`: `{}` is not an array index",
+                    &rest[1..end]
+                )
+            })?;
+            segments.push(PathSegment::Index(index));
+            rest = &rest[end + 1..];
+        }
+    }
+    Ok(segments)
+}
+
+/// Replaces the `~1` and `~0` escapes of a JSON Pointer reference token, returning `None` if `~`
+/// is followed by anything else.
+fn unescape_pointer_token(token: &str) -> Option<Cow<'_, str>> {
+    if !token.contains('~') {
+        return Some(Cow::Borrowed(token));
+    }
+    let mut unescaped = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '~' => match chars.next() {
+                Some('0') => unescaped.push('~'),
+                Some('1') => unescaped.push('/'),
+                _ => return None,
+            },
+            c => unescaped.push(c),
+        }
+    }
+    Some(Cow::Owned(unescaped))
+}
+
+/// Parses a JSON Pointer array index, which is `0` or digits without a leading zero.
+fn parse_pointer_index(token: &str) -> Option<u32> {
+    let digits = !token.is_empty() && token.bytes().all(|b| b.is_ascii_digit());
+    if !digits || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+    token.parse().ok()
+}
+
 // We can't implement From<JSValueRef> for JSValueRaw, as
 // JSValueRaw is automatically generated and it would result
 // in a cyclic crate dependency.
@@ -423,6 +1537,23 @@ impl Into<JSValueRaw> for JSValueRef<'_> {
     }
 }
 
+/// Appends `s` to `out` as a quoted JSON string.
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
 impl fmt::Display for JSValueRef<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.to_js_value().unwrap())
@@ -511,6 +1642,29 @@ mod tests {
         assert_eq!("world", val.as_str().unwrap());
     }
 
+    #[test]
+    fn test_value_arrays_can_be_built_with_push_and_indexed_set() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let seq = ctx.array_value()?;
+        assert_eq!(0, seq.array_length()?);
+        assert_eq!(1, seq.push(ctx.value_from_i32(1)?)?);
+        assert_eq!(2, seq.push(ctx.value_from_i32(2)?)?);
+
+        seq.set_indexed_property(0, ctx.value_from_str("first")?)?;
+        seq.set_indexed_property(3, ctx.value_from_i32(4)?)?;
+        assert_eq!(4, seq.array_length()?);
+
+        let global = ctx.global_object()?;
+        global.set_property("seq", seq)?;
+        let json = ctx.eval_global(SCRIPT_NAME, "JSON.stringify(seq)")?;
+        assert_eq!(r#"["first",2,null,4]"#, json.as_str()?);
+
+        let obj = ctx.object_value()?;
+        assert!(obj.array_length().is_err());
+        assert!(obj.push(ctx.value_from_i32(1)?).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_value_set_property_returns_exception() {
         let ctx = JSContextRef::default();
@@ -551,6 +1705,130 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_value_get_indexed_range() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global(SCRIPT_NAME, "[10, 20, 30, 40]")?;
+
+        let range = val.get_indexed_range(1, 2)?;
+        let range: Vec<i32> = range.iter().map(|v| v.as_i32_unchecked()).collect();
+        assert_eq!(vec![20, 30], range);
+
+        let past_the_end = val.get_indexed_range(3, 2)?;
+        assert_eq!(40, past_the_end[0].as_i32_unchecked());
+        assert!(past_the_end[1].is_undefined());
+        assert!(val.get_indexed_property(100)?.is_undefined());
+
+        assert!(val.get_indexed_range(2, 0)?.is_empty());
+        assert!(val.get_indexed_range(u32::MAX, 2).is_err());
+
+        // Arrays with holes and array-like objects are looked up an index at a time.
+        for source in [
+            "var a = [1, 2]; a.length = 4; a",
+            "var a = []; a[3] = 4; a[1] = 2; a",
+            "({0: 1, 1: 2, 3: 4, length: 4})",
+        ] {
+            let val = ctx.eval_global(SCRIPT_NAME, source)?;
+            let range = val.get_indexed_range(0, 4)?;
+            for (index, element) in range.iter().enumerate() {
+                let expected = val.get_indexed_property(index as u32)?;
+                assert_eq!(expected.is_undefined(), element.is_undefined());
+                if !expected.is_undefined() {
+                    assert_eq!(expected.as_i32_unchecked(), element.as_i32_unchecked());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_get_path() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global(
+            SCRIPT_NAME,
+            "({config: {db: {host: 'localhost'}}, items: [{name: 'a'}, {name: 'b', tags: [[1, 2]]}]})",
+        )?;
+
+        assert_eq!("localhost", val.get_path("config.db.host")?.as_str()?);
+        assert_eq!("b", val.get_path("items[1].name")?.as_str()?);
+        assert_eq!(2, val.get_path("items[1].tags[0][1]")?.try_as_integer()?);
+        assert!(val.get_path("")?.is_object());
+
+        assert!(val.get_path("config.cache.host")?.is_undefined());
+        assert!(val.get_path("items[5].name")?.is_undefined());
+        assert_eq!(
+            "Path `config.cache.host` does not exist",
+            val.get_path_strict("config.cache.host")
+                .unwrap_err()
+                .to_string()
+        );
+        assert!(val.get_path_strict("config.db.port").is_err());
+
+        assert_eq!(
+            "Invalid path `items[x]`: `x` is not an array index",
+            val.get_path("items[x]").unwrap_err().to_string()
+        );
+        assert!(val.get_path("config..db").is_err());
+        assert!(val.get_path("items[0").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_resolve_pointer() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global(
+            SCRIPT_NAME,
+            "({a: [{b: 'first'}, {b: 'second'}], 'c/d': 1, 'e~f': 2, '': 3, nested: {'': {x: 4}}})",
+        )?;
+
+        assert!(val.resolve_pointer("")?.is_object());
+        assert_eq!("second", val.resolve_pointer("/a/1/b")?.as_str()?);
+        assert_eq!(1, val.resolve_pointer("/c~1d")?.try_as_integer()?);
+        assert_eq!(2, val.resolve_pointer("/e~0f")?.try_as_integer()?);
+        assert_eq!(3, val.resolve_pointer("/")?.try_as_integer()?);
+        assert_eq!(4, val.resolve_pointer("/nested//x")?.try_as_integer()?);
+        assert!(val.resolve_pointer("/a")?.is_array());
+
+        for pointer in [
+            "/missing",
+            "/a/2",
+            "/a/-",
+            "/a/01",
+            "/a/x",
+            "/a/0/b/c",
+            "/toString",
+        ] {
+            assert_eq!(
+                format!("JSON pointer `{pointer}` does not exist"),
+                val.resolve_pointer(pointer).unwrap_err().to_string()
+            );
+        }
+        assert_eq!(
+            "Invalid JSON pointer `a/0`: must start with `/`",
+            val.resolve_pointer("a/0").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "Invalid JSON pointer `/e~2f`: invalid escape",
+            val.resolve_pointer("/e~2f").unwrap_err().to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_as_array_vec() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global(SCRIPT_NAME, "[1, 'two', [3]]")?;
+        let elements = val.as_array_vec()?;
+        assert_eq!(3, elements.len());
+        assert_eq!(1, elements[0].try_as_integer()?);
+        assert_eq!("two", elements[1].as_str()?);
+        assert!(elements[2].is_array());
+
+        let val = ctx.eval_global(SCRIPT_NAME, "({0: 'a', length: 1})")?;
+        assert!(val.as_array_vec().is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_allows_representing_a_value_as_f64() -> Result<()> {
         let ctx = JSContextRef::default();
@@ -559,6 +1837,244 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_coerce_to_bool() -> Result<()> {
+        let ctx = JSContextRef::default();
+        for (source, expected) in [
+            ("true", true),
+            ("0", false),
+            ("1", true),
+            ("''", false),
+            ("'false'", true),
+            ("null", false),
+            ("undefined", false),
+            ("({})", true),
+            ("NaN", false),
+        ] {
+            let val = ctx.eval_global(SCRIPT_NAME, source)?;
+            assert_eq!(expected, val.coerce_to_bool()?, "{source}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_prototype_chain_properties() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global(
+            SCRIPT_NAME,
+            r#"
+            class Animal {
+                constructor() { this.legs = 4; }
+                describe() { return "animal"; }
+                eat() {}
+            }
+            class Dog extends Animal {
+                constructor() { super(); this.name = "Rex"; }
+                describe() { return "dog"; }
+            }
+            new Dog()
+            "#,
+        )?;
+
+        let properties = val.prototype_chain_properties()?;
+        let names: Vec<&str> = properties.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            ["legs", "name", "constructor", "describe", "eat"],
+            names.as_slice()
+        );
+
+        let (_, describe) = &properties[3];
+        assert_eq!("dog", describe.call(&val, &[])?.as_str()?);
+        assert!(ctx.value_from_i32(1)?.prototype_chain_properties().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_content_hash() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let hash = |source: &str| ctx.eval_global(SCRIPT_NAME, source)?.content_hash();
+
+        let expected = hash("({a: 1, b: [true, null, 'x'], c: {d: 2.5}})")?;
+        assert_eq!(
+            expected,
+            hash("({c: {d: 2.5}, b: [true, null, 'x'], a: 1.0})")?
+        );
+        assert_ne!(
+            expected,
+            hash("({a: 1, b: [true, null, 'y'], c: {d: 2.5}})")?
+        );
+        assert_ne!(
+            expected,
+            hash("({a: 1, b: [true, null, 'x'], c: {e: 2.5}})")?
+        );
+
+        assert_eq!(hash("NaN")?, hash("0 / 0")?);
+        assert_eq!(hash("0")?, hash("-0")?);
+        assert_ne!(hash("1")?, hash("'1'")?);
+        assert_ne!(hash("['ab', 'c']")?, hash("['a', 'bc']")?);
+        assert_ne!(hash("[]")?, hash("({})")?);
+
+        assert_eq!(hash("new Date(0)")?, hash("new Date(0)")?);
+        assert_ne!(hash("new Date(0)")?, hash("new Date(1)")?);
+        assert_ne!(hash("new Date(0)")?, hash("({})")?);
+        assert_ne!(hash("new Date(0)")?, hash("0")?);
+        assert_eq!(hash("new Map([['a', 1]])")?, hash("new Map([['a', 1.0]])")?);
+        assert_ne!(hash("new Map([['a', 1]])")?, hash("new Map([['a', 2]])")?);
+        assert_ne!(hash("new Map([['a', 1]])")?, hash("new Map([['b', 1]])")?);
+        assert_ne!(hash("new Map()")?, hash("({})")?);
+        assert_ne!(hash("new Map([['a', 1]])")?, hash("({a: 1})")?);
+        assert_ne!(hash("new Set([1])")?, hash("new Set([2])")?);
+        assert_ne!(hash("new Set()")?, hash("({})")?);
+        assert_ne!(hash("new Set()")?, hash("new Map()")?);
+        assert_ne!(hash("new Set([1, 2])")?, hash("[1, 2]")?);
+        assert!(hash("var m = new Map(); m.set('m', m); m").is_err());
+
+        assert!(hash("({f() {}})").is_err());
+        assert!(hash("var o = {}; o.o = o; o").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_structured_clone() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let original = ctx.eval_global(
+            SCRIPT_NAME,
+            r#"
+            var shared = {id: 1};
+            var original = {
+                user: {name: "a", tags: ["x", "y"]},
+                first: shared,
+                second: shared,
+                bytes: new Uint8Array([1, 2, 3]).subarray(1),
+                lookup: new Map([["k", {v: 1}]]),
+                unique: new Set([1, 2]),
+                when: new Date(0),
+            };
+            original.self = original;
+            original
+            "#,
+        )?;
+        let copy = original.structured_clone()?;
+        ctx.global_object()?.set_property("copy", copy)?;
+
+        ctx.eval_global(
+            SCRIPT_NAME,
+            r#"
+            original.user.name = "b";
+            original.user.tags.push("z");
+            shared.id = 2;
+            original.bytes[0] = 9;
+            original.lookup.get("k").v = 2;
+            original.unique.add(3);
+            original.when.setTime(1000);
+            "#,
+        )?;
+
+        let checks = ctx.eval_global(
+            SCRIPT_NAME,
+            r#"
+            [
+                copy !== original,
+                copy.user.name === "a",
+                copy.user.tags.join() === "x,y",
+                copy.first.id === 1 && copy.first === copy.second,
+                copy.self === copy,
+                copy.bytes instanceof Uint8Array && copy.bytes.join() === "2,3",
+                copy.bytes.buffer.byteLength === 3,
+                copy.lookup.get("k").v === 1,
+                copy.unique.size === 2,
+                copy.when.getTime() === 0,
+            ]
+            "#,
+        )?;
+        for (i, check) in checks.as_array_vec()?.iter().enumerate() {
+            assert!(check.as_bool()?, "check {i}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_structured_clone_errors() -> Result<()> {
+        let ctx = JSContextRef::default();
+        for source in [
+            "({f: function () {}})",
+            "[Symbol()]",
+            "({m: new WeakMap()})",
+            "Promise.resolve()",
+        ] {
+            let val = ctx.eval_global(SCRIPT_NAME, source)?;
+            assert!(val.structured_clone().is_err(), "{source}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_as_f64_strict_and_lossy() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let numeric_string = ctx.eval_global(SCRIPT_NAME, "'42'")?;
+        assert!(numeric_string.as_f64_strict().is_err());
+        assert_eq!(42.0, numeric_string.as_f64_lossy()?);
+
+        let number = ctx.eval_global(SCRIPT_NAME, "42.5")?;
+        assert_eq!(42.5, number.as_f64_strict()?);
+        assert_eq!(42.5, number.as_f64_lossy()?);
+
+        let text = ctx.eval_global(SCRIPT_NAME, "'forty-two'")?;
+        assert!(text.as_f64_strict().is_err());
+        assert!(text.as_f64_lossy()?.is_nan());
+        Ok(())
+    }
+
+    #[test]
+    fn test_coerce_to_number() -> Result<()> {
+        let ctx = JSContextRef::default();
+        for (source, expected) in [
+            ("1.5", 1.5),
+            ("' 42 '", 42.0),
+            ("true", 1.0),
+            ("null", 0.0),
+            ("[7]", 7.0),
+            ("({ valueOf() { return 3; } })", 3.0),
+        ] {
+            let val = ctx.eval_global(SCRIPT_NAME, source)?;
+            assert_eq!(expected, val.coerce_to_number()?, "{source}");
+        }
+
+        assert!(ctx
+            .eval_global(SCRIPT_NAME, "'abc'")?
+            .coerce_to_number()?
+            .is_nan());
+        let val = ctx.eval_global(SCRIPT_NAME, "({ valueOf() { throw new Error('nope'); } })")?;
+        assert!(val.coerce_to_number().is_err());
+        assert!(ctx
+            .eval_global(SCRIPT_NAME, "Symbol()")?
+            .coerce_to_number()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_coerce_to_string() -> Result<()> {
+        let ctx = JSContextRef::default();
+        for (source, expected) in [
+            ("'hello'", "hello"),
+            ("1.5", "1.5"),
+            ("undefined", "undefined"),
+            ("[1, [2, 3], null]", "1,2,3,"),
+            ("({})", "[object Object]"),
+            ("({ toString() { return 'custom'; } })", "custom"),
+        ] {
+            let val = ctx.eval_global(SCRIPT_NAME, source)?;
+            assert_eq!(expected, val.coerce_to_string()?, "{source}");
+        }
+
+        assert!(ctx
+            .eval_global(SCRIPT_NAME, "Symbol()")?
+            .coerce_to_string()
+            .is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_value_as_str() {
         let s = "hello";
@@ -567,6 +2083,24 @@ mod tests {
         assert_eq!(val.as_str().unwrap(), s);
     }
 
+    #[test]
+    fn test_value_as_str_reader() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global(
+            SCRIPT_NAME,
+            "JSON.stringify(Array.from({length: 100000}, (_, i) => ({id: i, name: 'é'})))",
+        )?;
+
+        let mut streamed = Vec::new();
+        let copied = std::io::copy(&mut val.as_str_reader()?, &mut streamed)?;
+        assert_eq!(val.as_str()?.len() as u64, copied);
+        assert_eq!(val.as_str()?.as_bytes(), &streamed[..]);
+        assert!(streamed.starts_with(r#"[{"id":0,"name":"é"}"#.as_bytes()));
+
+        assert!(ctx.value_from_i32(1)?.as_str_reader().is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_value_as_str_middle_nul_terminator() {
         let s = "hello\0world!";
@@ -795,6 +2329,79 @@ mod tests {
             .is_function());
     }
 
+    #[test]
+    fn test_as_bytes_of_typed_array_views() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let view = ctx.eval_global(
+            "main",
+            "globalThis.whole = new Uint8Array([0, 1, 2, 3, 4, 5]); whole.subarray(2, 5)",
+        )?;
+        assert_eq!(&[2, 3, 4], view.as_bytes()?);
+
+        view.as_bytes_mut()?[0] = 42;
+        let whole = ctx.eval_global("main", "whole")?;
+        assert_eq!(&[0, 1, 42, 3, 4, 5], whole.as_bytes()?);
+
+        let words = ctx.eval_global("main", "new Uint16Array(new ArrayBuffer(8), 2, 2)")?;
+        assert_eq!(4, words.as_bytes()?.len());
+        assert!(ctx.eval_global("main", "[1, 2]")?.as_bytes().is_err());
+        assert!(ctx
+            .eval_global("main", "new DataView(new ArrayBuffer(2))")?
+            .as_bytes()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_array_buffer_bytes() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let buffer = ctx.eval_global("main", "new Uint8Array([1, 2, 3, 4]).buffer")?;
+        let sum =
+            buffer.with_array_buffer_bytes(|bytes| bytes.iter().map(|&b| b as u32).sum::<u32>())?;
+        assert_eq!(10, sum);
+
+        let view = ctx.eval_global("main", "new Uint8Array([1, 2, 3, 4]).subarray(1, 3)")?;
+        assert_eq!(vec![2, 3], view.with_array_buffer_bytes(<[u8]>::to_vec)?);
+
+        let err = ctx
+            .eval_global("main", "'bytes'")?
+            .with_array_buffer_bytes(|_| ())
+            .unwrap_err();
+        assert!(err.to_string().contains("array buffer"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_negative_zero() -> Result<()> {
+        let ctx = JSContextRef::default();
+        assert!(ctx.eval_global("main", "-0")?.is_negative_zero());
+        assert!(ctx.eval_global("main", "0 * -1")?.is_negative_zero());
+        assert!(!ctx.eval_global("main", "0")?.is_negative_zero());
+        assert!(!ctx.eval_global("main", "0.0")?.is_negative_zero());
+        assert!(!ctx.eval_global("main", "'-0'")?.is_negative_zero());
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_callable() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let proxy = ctx.eval_global(
+            "main",
+            "new Proxy(function () {}, { apply: (target, receiver, args) => args[0] * 2 })",
+        )?;
+        assert!(proxy.is_callable());
+        assert!(proxy.is_function());
+        let arg = ctx.value_from_i32(21)?;
+        let result = proxy.call(&ctx.undefined_value()?, &[arg])?;
+        assert_eq!(42, result.as_i32_unchecked());
+
+        let uncallable = ctx.eval_global("main", "new Proxy({}, { apply: () => 1 })")?;
+        assert!(!uncallable.is_callable());
+        assert!(uncallable.call(&ctx.undefined_value()?, &[]).is_err());
+        assert!(!ctx.value_from_i32(1)?.is_callable());
+        Ok(())
+    }
+
     #[test]
     fn test_eval_function() {
         let ctx = JSContextRef::default();
@@ -945,4 +2552,276 @@ mod tests {
         assert_eq!(expected, arg);
         Ok(())
     }
+
+    #[test]
+    fn test_for_each_entry() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global("main", "({a: 1, b: 'two', c: [3]})")?;
+
+        let mut entries = vec![];
+        val.for_each_entry(|key, value| {
+            entries.push((key.to_string(), value.to_string()));
+            Ok(())
+        })?;
+
+        assert_eq!(
+            vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "two".to_string()),
+                ("c".to_string(), "3".to_string()),
+            ],
+            entries
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_for_each_entry_stops_at_the_first_error() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global("main", "({a: 1, b: 2, c: 3})")?;
+
+        let mut visited = vec![];
+        let err = val
+            .for_each_entry(|key, _| {
+                visited.push(key.to_string());
+                if key == "b" {
+                    anyhow::bail!("stop at {key}");
+                }
+                Ok(())
+            })
+            .unwrap_err();
+
+        assert_eq!("stop at b", err.to_string());
+        assert_eq!(vec!["a", "b"], visited);
+        assert!(ctx
+            .value_from_i32(1)?
+            .for_each_entry(|_, _| Ok(()))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_has_and_delete_property() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global(
+            "main",
+            "Object.defineProperty(Object.create({inherited: 1}, {}), 'fixed', {value: 1})",
+        )?;
+        val.set_property("defined", ctx.value_from_i32(1)?)?;
+        val.set_property("unset", ctx.undefined_value()?)?;
+
+        assert!(val.has_own_property("defined")?);
+        assert!(val.has_own_property("unset")?);
+        assert!(!val.has_own_property("missing")?);
+        assert!(!val.has_own_property("inherited")?);
+        assert!(val.has_property("unset")?);
+        assert!(val.has_property("inherited")?);
+        assert!(!val.has_property("missing")?);
+
+        assert!(val.delete_property("unset")?);
+        assert!(!val.has_own_property("unset")?);
+        assert!(val.delete_property("missing")?);
+        assert!(!val.delete_property("fixed")?);
+        assert!(val.has_own_property("fixed")?);
+
+        assert!(ctx.value_from_i32(1)?.has_property("a").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_own_keys() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global(
+            "main",
+            "({userId: 1, 'kebab-case': 2, 10: 3, [Symbol('s')]: 4, \
+              get broken() { throw new Error('read'); }})",
+        )?;
+        assert_eq!(
+            vec!["10", "userId", "kebab-case", "broken"],
+            val.own_keys()?
+        );
+
+        let val = ctx.eval_global("main", "Object.create({inherited: 1})")?;
+        assert!(val.own_keys()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_utf16_len() -> Result<()> {
+        let ctx = JSContextRef::default();
+        for (text, utf16_len) in [("javy", 4), ("é", 1), ("😀", 2), ("e\u{301}", 2), ("𝄞a", 3)]
+        {
+            let val = ctx.value_from_str(text)?;
+            assert_eq!(utf16_len, val.utf16_len()?, "{text}");
+            assert_eq!(text.len(), val.as_str()?.len(), "{text}");
+        }
+        assert!(ctx.value_from_i32(1)?.utf16_len().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncated_str() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.value_from_str("a😀b")?;
+        assert_eq!("", val.truncated_str(0)?);
+        assert_eq!("a", val.truncated_str(1)?);
+        // Half of the surrogate pair doesn't fit, so the emoji is dropped as a whole.
+        assert_eq!("a", val.truncated_str(2)?);
+        assert_eq!("a😀", val.truncated_str(3)?);
+        assert_eq!("a😀b", val.truncated_str(4)?);
+        assert_eq!("a😀b", val.truncated_str(100)?);
+
+        // The combining acute accent is a separate code point from the `e` it modifies.
+        let val = ctx.value_from_str("e\u{301}x")?;
+        assert_eq!("e", val.truncated_str(1)?);
+        assert_eq!("e\u{301}", val.truncated_str(2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_error() -> Result<()> {
+        let ctx = JSContextRef::default();
+        assert!(ctx.eval_global("main", "new Error('oops')")?.is_error());
+        assert!(ctx
+            .eval_global("main", "new RangeError('oops')")?
+            .is_error());
+        assert!(!ctx.eval_global("main", "({message: 'oops'})")?.is_error());
+        assert!(!ctx.value_from_str("oops")?.is_error());
+        Ok(())
+    }
+
+    #[test]
+    fn test_freeze() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let config = ctx.eval_global(
+            "main",
+            "globalThis.config = {mode: 'safe', nested: {}}; config",
+        )?;
+        assert!(!config.is_frozen()?);
+
+        config.freeze()?;
+        assert!(config.is_frozen()?);
+        let err = ctx
+            .eval_global("main", "'use strict'; config.mode = 'unsafe';")
+            .unwrap_err();
+        assert!(err.to_string().contains("TypeError"));
+        ctx.eval_global("main", "config.extra = true; delete config.mode;")?;
+        assert_eq!("safe", config.get_property("mode")?.as_str()?);
+        assert!(config.get_property("extra")?.is_undefined());
+
+        // Freezing is shallow.
+        assert!(!config.get_property("nested")?.is_frozen()?);
+        assert!(ctx.value_from_i32(1)?.is_frozen()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_json() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global(
+            "main",
+            r#"({name: 'caf\u00e9 \u{1F600}', tags: ['a', null], n: 1.5, skipped: undefined})"#,
+        )?;
+        let json = val.to_json_string()?;
+        assert_eq!(r#"{"name":"café 😀","tags":["a",null],"n":1.5}"#, json);
+        assert_eq!(json.as_bytes(), val.to_json_bytes()?.as_slice());
+        let lone_surrogate = ctx.eval_global("main", r"'\ud800'")?;
+        assert_eq!(br#""\ud800""#, lone_surrogate.to_json_bytes()?.as_slice());
+
+        for source in ["undefined", "(() => 1)", "var a = {}; a.a = a; a", "1n"] {
+            let val = ctx.eval_global("main", source)?;
+            assert!(val.to_json_string().is_err(), "{source}");
+            assert!(val.to_json_bytes().is_err(), "{source}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_integer() -> Result<()> {
+        let ctx = JSContextRef::default();
+        for (source, expected) in [
+            ("5", true),
+            ("5.0", true),
+            ("-5", true),
+            ("5.5", false),
+            ("2 ** 53", true),
+            ("-(2 ** 60)", true),
+            ("0.1 + 0.2", false),
+            ("NaN", false),
+            ("Infinity", false),
+            ("'5'", false),
+            ("5n", false),
+        ] {
+            let val = ctx.eval_global("main", source)?;
+            assert_eq!(expected, val.is_integer(), "{source}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_as_accessors() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let number = ctx.eval_global("main", "2.5")?;
+        assert_eq!(Some(2.5), number.try_as_f64());
+        assert_eq!(None, number.try_as_i32());
+        assert_eq!(None, number.try_as_str());
+
+        let integer = ctx.eval_global("main", "-7")?;
+        assert_eq!(Some(-7.0), integer.try_as_f64());
+        assert_eq!(Some(-7), integer.try_as_i32());
+        assert_eq!(None, ctx.eval_global("main", "2 ** 40")?.try_as_i32());
+
+        let string = ctx.eval_global("main", "'42'")?;
+        assert_eq!(Some("42"), string.try_as_str());
+        assert_eq!(None, string.try_as_f64());
+        assert_eq!(None, string.try_as_i32());
+
+        for source in ["null", "undefined", "true", "({})", "[1]"] {
+            let val = ctx.eval_global("main", source)?;
+            assert_eq!(None, val.try_as_f64(), "{source}");
+            assert_eq!(None, val.try_as_i32(), "{source}");
+            assert_eq!(None, val.try_as_str(), "{source}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_debug_json() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global(
+            "main",
+            "({name: 'javy', run() {}, id: Symbol('id'), nested: {list: [1, 2.5, null, undefined]}, big: 10n, text: 'say \"hi\"\\n'})",
+        )?;
+
+        assert_eq!(
+            r#"{"name": "javy", "run": "[Function]", "id": "[Symbol]", "nested": {"list": [1, 2.5, null, undefined]}, "big": 10n, "text": "say \"hi\"\n"}"#,
+            val.to_debug_json(5, 1000)
+        );
+        assert_eq!(
+            r#"{"name": "javy", "run": "[Function]", "id": "[Symbol]", "nested": "[Object]", "big": 10n, "text": "say \"hi\"\n"}"#,
+            val.to_debug_json(1, 1000)
+        );
+        assert_eq!(r#""[Object]""#, val.to_debug_json(0, 1000));
+        assert_eq!(r#"{"name": "javy", "#, &val.to_debug_json(5, 20)[..17]);
+        assert!(val.to_debug_json(5, 20).ends_with("..."));
+        assert_eq!(20, val.to_debug_json(5, 20).chars().count());
+
+        let val = ctx.eval_global("main", "[new TypeError('bad'), () => 1, NaN]")?;
+        assert_eq!(
+            r#"["[TypeError: bad]", "[Function]", NaN]"#,
+            val.to_debug_json(2, 1000)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_debug_json_on_circular_values() -> Result<()> {
+        let ctx = JSContextRef::default();
+        let val = ctx.eval_global("main", "var a = {n: 1}; a.self = a; a")?;
+        assert_eq!(
+            r#"{"n": 1, "self": {"n": 1, "self": "[Object]"}}"#,
+            val.to_debug_json(2, 1000)
+        );
+        Ok(())
+    }
 }
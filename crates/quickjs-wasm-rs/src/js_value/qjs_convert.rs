@@ -1,12 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use anyhow::{bail, Result};
 use quickjs_wasm_sys::{
-    JS_TAG_BOOL, JS_TAG_INT, JS_TAG_NULL, JS_TAG_OBJECT, JS_TAG_STRING, JS_TAG_UNDEFINED,
+    JSValue as JSValueRaw, JS_TAG_BOOL, JS_TAG_INT, JS_TAG_NULL, JS_TAG_OBJECT, JS_TAG_STRING,
+    JS_TAG_UNDEFINED,
 };
 
 use super::JSValue;
-use crate::js_binding::{context::JSContextRef, value::JSValueRef};
+use crate::js_binding::{context::JSContextRef, properties::Properties, value::JSValueRef};
 
 /// Converts a reference to QuickJS value represented by `quickjs_wasm_rs::JSValueRef` to a `JSValue`.
 ///
@@ -69,6 +70,170 @@ pub fn from_qjs_value(val: JSValueRef) -> Result<JSValue> {
     Ok(js_val)
 }
 
+/// Converts a `JSValueRef` to a `JSValue` like [`from_qjs_value`], but walks nested arrays and
+/// objects with an explicit stack instead of recursing.
+///
+/// The recursive conversion uses native stack for every level of nesting, so deep but narrow
+/// values, like linked lists built out of objects, can overflow the stack. This conversion only
+/// uses heap memory for the levels it's inside of, at the cost of being a little slower for
+/// shallow values.
+///
+/// Only the conversion is iterative. The returned `JSValue` is still dropped recursively, which
+/// takes far less stack per level than converting but can overflow too for values nested tens of
+/// thousands of levels deep, unless they're taken apart a level at a time. Deserializing with
+/// [`Deserializer`](crate::Deserializer) also still recurses for every level of nesting.
+///
+/// An array or object that contains itself, like `o` after `o.o = o`, fails with a
+/// `circular reference` error.
+///
+/// # Example
+///
+/// ```
+/// let context = JSContextRef::default();
+/// let list = context.eval_global(
+///     "list.js",
+///     "let list = null; for (let i = 0; i < 1000; i++) list = { next: list }; list",
+/// )?;
+/// let js_val = from_qjs_value_iterative(list)?;
+/// ```
+pub fn from_qjs_value_iterative(val: JSValueRef) -> Result<JSValue> {
+    let mut stack: Vec<Container> = Vec::new();
+    // The containers on the stack, to detect values that contain themselves.
+    let mut ancestors: HashSet<JSValueRaw> = HashSet::new();
+    let mut next = Some(val);
+    loop {
+        let mut done = match next.take().map(Container::new).transpose()? {
+            Some(Ok(container)) => {
+                if !ancestors.insert(container.value().into()) {
+                    bail!("circular reference");
+                }
+                stack.push(container);
+                None
+            }
+            Some(Err(leaf)) => Some(leaf),
+            None => None,
+        };
+
+        // Hand the finished value to its parent and move on to the parent's next child, finishing
+        // every container that has no children left on the way up.
+        loop {
+            let Some(container) = stack.last_mut() else {
+                // Only reached once the outermost value is finished.
+                return Ok(done.unwrap_or(JSValue::Undefined));
+            };
+            if let Some(value) = done.take() {
+                container.push(value);
+            }
+            match container.next_child()? {
+                Some(child) => {
+                    next = Some(child);
+                    break;
+                }
+                None => {
+                    done = stack.pop().map(|container| {
+                        ancestors.remove(&container.value().into());
+                        container.finish()
+                    })
+                }
+            }
+        }
+    }
+}
+
+/// An array or object that [`from_qjs_value_iterative`] is in the middle of converting.
+enum Container<'a> {
+    Array {
+        array: JSValueRef<'a>,
+        len: usize,
+        items: Vec<JSValue>,
+    },
+    Object {
+        object: JSValueRef<'a>,
+        properties: Properties<'a>,
+        key: Option<String>,
+        entries: HashMap<String, JSValue>,
+    },
+}
+
+impl<'a> Container<'a> {
+    /// Returns a container for arrays and objects, and the converted value for anything else.
+    fn new(val: JSValueRef<'a>) -> Result<std::result::Result<Self, JSValue>> {
+        if val.get_tag() != JS_TAG_OBJECT || val.is_array_buffer() {
+            return from_qjs_value(val).map(Err);
+        }
+        let container = if val.is_array() {
+            let len = from_qjs_value(val.get_property("length")?)?.try_into()?;
+            Container::Array {
+                array: val,
+                len,
+                items: Vec::with_capacity(len),
+            }
+        } else {
+            Container::Object {
+                object: val,
+                properties: val.properties()?,
+                key: None,
+                entries: HashMap::new(),
+            }
+        };
+        Ok(Ok(container))
+    }
+
+    /// Returns the array or object being converted.
+    fn value(&self) -> JSValueRef<'a> {
+        match self {
+            Container::Array { array, .. } => *array,
+            Container::Object { object, .. } => *object,
+        }
+    }
+
+    /// Returns the next element or property value to convert, if there is one.
+    fn next_child(&mut self) -> Result<Option<JSValueRef<'a>>> {
+        match self {
+            Container::Array { array, len, items } => {
+                if items.len() < *len {
+                    Ok(Some(array.get_indexed_property(items.len().try_into()?)?))
+                } else {
+                    Ok(None)
+                }
+            }
+            Container::Object {
+                object,
+                properties,
+                key,
+                ..
+            } => match properties.next_key()? {
+                Some(property_key) => {
+                    let property_key = property_key.as_str()?;
+                    let value = object.get_property(property_key)?;
+                    *key = Some(property_key.to_string());
+                    Ok(Some(value))
+                }
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// Adds the converted value of the child last returned by [`Container::next_child`].
+    fn push(&mut self, value: JSValue) {
+        match self {
+            Container::Array { items, .. } => items.push(value),
+            Container::Object { key, entries, .. } => {
+                if let Some(key) = key.take() {
+                    entries.insert(key, value);
+                }
+            }
+        }
+    }
+
+    fn finish(self) -> JSValue {
+        match self {
+            Container::Array { items, .. } => JSValue::Array(items),
+            Container::Object { entries, .. } => JSValue::Object(entries),
+        }
+    }
+}
+
 /// Converts a reference to a `JSValue` to a `quickjs_wasm_rs::JSValueRef`.
 ///
 /// # Arguments
@@ -204,6 +369,129 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_from_qjs_iterative_matches_recursive() {
+        let context = JSContextRef::default();
+        let qjs_val = context
+            .eval_global(
+                "test.js",
+                "({a: [1, 2.5, 'three', [], {}], b: {c: null, d: [undefined, true]}, e: new ArrayBuffer(2)})",
+            )
+            .unwrap();
+        assert_eq!(
+            from_qjs_value(qjs_val).unwrap(),
+            from_qjs_value_iterative(qjs_val).unwrap()
+        );
+
+        let qjs_val = context.eval_global("test.js", "42").unwrap();
+        assert_eq!(from_qjs_value_iterative(qjs_val).unwrap(), 42.into());
+    }
+
+    #[test]
+    fn test_from_qjs_iterative_circular_reference() {
+        let context = JSContextRef::default();
+        for source in [
+            "var o = {}; o.o = o; o",
+            "var a = [1]; a.push({items: a}); a",
+        ] {
+            let qjs_val = context.eval_global("test.js", source).unwrap();
+            let err = from_qjs_value_iterative(qjs_val).unwrap_err();
+            assert_eq!("circular reference", err.to_string());
+        }
+
+        // Values referenced more than once without a cycle are converted for each reference.
+        let qjs_val = context
+            .eval_global("test.js", "var s = {v: 1}; ({a: s, b: [s]})")
+            .unwrap();
+        assert_eq!(
+            from_qjs_value(qjs_val).unwrap(),
+            from_qjs_value_iterative(qjs_val).unwrap()
+        );
+    }
+
+    const DEPTH: usize = 10000;
+
+    /// Evaluates a value nested `DEPTH` levels deep, alternating between arrays and objects.
+    fn deeply_nested(context: &JSContextRef) -> JSValueRef {
+        context
+            .eval_global(
+                "test.js",
+                &format!(
+                    "let v = 0; for (let i = 0; i < {DEPTH}; i++) v = i % 2 ? [v] : {{next: v}}; v"
+                ),
+            )
+            .unwrap()
+    }
+
+    /// Takes a value built by [`deeply_nested`] apart a level at a time, returning its depth.
+    /// Dropping it recursively would overflow the stack.
+    fn take_apart(mut js_val: JSValue) -> usize {
+        let mut depth = 0;
+        loop {
+            js_val = match js_val {
+                JSValue::Array(mut items) => items.pop().unwrap(),
+                JSValue::Object(mut entries) => entries.remove("next").unwrap(),
+                leaf => {
+                    assert_eq!(leaf, 0.into());
+                    return depth;
+                }
+            };
+            depth += 1;
+        }
+    }
+
+    #[test]
+    fn test_from_qjs_iterative_deeply_nested() {
+        let context = JSContextRef::default();
+        let js_val = from_qjs_value_iterative(deeply_nested(&context)).unwrap();
+        assert_eq!(DEPTH, take_apart(js_val));
+    }
+
+    #[test]
+    #[ignore = "overflows the stack, run by test_from_qjs_recursive_overflows_deeply_nested"]
+    fn convert_deeply_nested_recursively() {
+        let context = JSContextRef::default();
+        let js_val = from_qjs_value(deeply_nested(&context)).unwrap();
+        std::mem::forget(js_val);
+    }
+
+    #[test]
+    #[ignore = "run by test_from_qjs_recursive_overflows_deeply_nested"]
+    fn convert_deeply_nested_iteratively() {
+        let context = JSContextRef::default();
+        let js_val = from_qjs_value_iterative(deeply_nested(&context)).unwrap();
+        assert_eq!(DEPTH, take_apart(js_val));
+    }
+
+    /// Spawning processes isn't supported on WASI, so this only runs on native targets.
+    #[test]
+    #[cfg(not(target_family = "wasm"))]
+    fn test_from_qjs_recursive_overflows_deeply_nested() {
+        // Runs one of the ignored tests above in a new process, since overflowing the stack
+        // aborts the process. Both get the same 512 KiB of stack.
+        let run = |name: &str| {
+            std::process::Command::new(std::env::current_exe().unwrap())
+                .args([
+                    "--ignored",
+                    "--exact",
+                    &format!("js_value::qjs_convert::tests::{name}"),
+                ])
+                .env("RUST_MIN_STACK", (512 * 1024).to_string())
+                .output()
+                .unwrap()
+        };
+
+        let output = run("convert_deeply_nested_iteratively");
+        assert!(output.status.success(), "{output:?}");
+
+        let output = run("convert_deeply_nested_recursively");
+        assert!(!output.status.success());
+        assert!(
+            String::from_utf8_lossy(&output.stderr).contains("has overflowed its stack"),
+            "{output:?}"
+        );
+    }
+
     #[test]
     fn test_to_qjs_null() {
         let context = JSContextRef::default();
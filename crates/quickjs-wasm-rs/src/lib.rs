@@ -66,11 +66,17 @@ mod js_binding;
 mod js_value;
 mod serialize;
 
-pub use crate::js_binding::context::JSContextRef;
+pub use crate::js_binding::context::{Intrinsics, JSContextRef};
 pub use crate::js_binding::error::JSError;
 pub use crate::js_binding::exception::Exception;
+pub use crate::js_binding::memory::MemoryStats;
+pub use crate::js_binding::modules;
+pub use crate::js_binding::pool::{ContextPool, PooledContext};
 pub use crate::js_binding::value::JSValueRef;
 pub use crate::js_value::qjs_convert::*;
 pub use crate::js_value::JSValue;
-pub use crate::serialize::de::Deserializer;
-pub use crate::serialize::ser::Serializer;
+pub use crate::serialize::adapters;
+pub use crate::serialize::de::{Case, Deserializer, DisallowedKeys, HookAction};
+pub use crate::serialize::err::{Error as SerdeError, ErrorKind as SerdeErrorKind};
+pub use crate::serialize::schema::Schema;
+pub use crate::serialize::ser::{OnCollision, Serializer};
@@ -0,0 +1,122 @@
+//! Maps a `bytes::Bytes` to and from an `ArrayBuffer`.
+//!
+//! `Bytes` are serialized as an `ArrayBuffer`. They are deserialized from an `ArrayBuffer`, a
+//! `Uint8Array` or an array of numbers. An `ArrayBuffer`'s contents are copied once, straight into
+//! the `Bytes`, while typed arrays and arrays are read element by element.
+//!
+//! Only available with the `bytes` feature.
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Deserialize)]
+//! struct Upload {
+//!     #[serde(with = "quickjs_wasm_rs::adapters::bytes")]
+//!     body: Bytes,
+//! }
+//! ```
+
+use ::bytes::Bytes;
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::Serializer;
+use std::fmt;
+
+pub fn serialize<S>(bytes: &Bytes, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_bytes(bytes)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Bytes, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Bytes;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an ArrayBuffer, a Uint8Array or an array of bytes")
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(Bytes::copy_from_slice(v))
+        }
+
+        fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(Bytes::from(v))
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            Ok(Bytes::from(bytes))
+        }
+    }
+
+    // Typed arrays are only deserialized as sequences when one is asked for, `ArrayBuffer`s are
+    // still visited as bytes.
+    deserializer.deserialize_seq(BytesVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use crate::serialize::ser::Serializer as ValueSerializer;
+    use ::bytes::Bytes;
+    use anyhow::Result;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct Upload {
+        #[serde(with = "super")]
+        body: Bytes,
+    }
+
+    fn deserialize(context: &JSContextRef, source: &str) -> Result<Upload> {
+        let val = context.eval_global("main", source)?;
+        Ok(Upload::deserialize(&mut ValueDeserializer::from(val))?)
+    }
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let context = JSContextRef::default();
+        let upload = Upload {
+            body: Bytes::from_static(&[1, 2, 255]),
+        };
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        upload.serialize(&mut serializer)?;
+        let body = serializer.value.get_property("body")?;
+        assert!(body.is_array_buffer());
+        assert_eq!(&[1, 2, 255], body.as_bytes()?);
+
+        let actual = Upload::deserialize(&mut ValueDeserializer::from(serializer.value))?;
+        assert_eq!(upload, actual);
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_arrays_and_arrays() -> Result<()> {
+        let context = JSContextRef::default();
+        for source in [
+            "({body: new Uint8Array([1, 2, 255])})",
+            "({body: new Uint8Array([0, 1, 2, 255]).subarray(1)})",
+            "({body: [1, 2, 255]})",
+        ] {
+            let upload = deserialize(&context, source)?;
+            assert_eq!(&[1, 2, 255], upload.body.as_ref(), "{source}");
+        }
+
+        assert!(deserialize(&context, "({body: [256]})").is_err());
+        assert!(deserialize(&context, "({body: 'text'})").is_err());
+        Ok(())
+    }
+}
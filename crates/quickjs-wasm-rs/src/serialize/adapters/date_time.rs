@@ -0,0 +1,132 @@
+//! Maps a `chrono::DateTime<Utc>` to and from a JavaScript `Date`.
+//!
+//! With [`Serializer`](crate::Serializer), date times are serialized as `Date` objects. Other
+//! serializers get the number of milliseconds since the Unix epoch, which is what `Date`
+//! represents. Since `Date` objects have no enumerable properties, date times are deserialized
+//! from milliseconds since the Unix epoch, like the result of `getTime()`, or from RFC 3339
+//! strings, like the result of `toISOString()`.
+//!
+//! Only available with the `chrono` feature.
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Serialize)]
+//! struct Event {
+//!     #[serde(with = "quickjs_wasm_rs::adapters::date_time")]
+//!     at: DateTime<Utc>,
+//! }
+//! ```
+
+use crate::serialize::ser::DATE_TOKEN;
+use chrono::{DateTime, Utc};
+use serde::de::{self, Deserializer, Visitor};
+use serde::ser::Serializer;
+use std::fmt;
+
+pub fn serialize<S>(date_time: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_newtype_struct(DATE_TOKEN, &date_time.timestamp_millis())
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct DateTimeVisitor;
+
+    impl<'de> Visitor<'de> for DateTimeVisitor {
+        type Value = DateTime<Utc>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("milliseconds since the Unix epoch or an RFC 3339 string")
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            DateTime::from_timestamp_millis(v)
+                .ok_or_else(|| E::custom(format!("timestamp {v} is out of range")))
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            let v = i64::try_from(v)
+                .map_err(|_| E::custom(format!("timestamp {v} is out of range")))?;
+            self.visit_i64(v)
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+            if !v.is_finite() {
+                return Err(E::custom("invalid date"));
+            }
+            self.visit_i64(v.trunc() as i64)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            DateTime::parse_from_rfc3339(v)
+                .map(|date_time| date_time.with_timezone(&Utc))
+                .map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_any(DateTimeVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use crate::serialize::ser::Serializer as ValueSerializer;
+    use anyhow::Result;
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct Event {
+        #[serde(with = "super")]
+        at: DateTime<Utc>,
+    }
+
+    #[test]
+    fn test_serializes_a_date() -> Result<()> {
+        let context = JSContextRef::default();
+        let event = Event {
+            at: DateTime::from_timestamp_millis(1_700_000_000_123).unwrap(),
+        };
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        event.serialize(&mut serializer)?;
+
+        let at = serializer.value.get_property("at")?;
+        assert!(at.is_instance_of_global("Date")?);
+        let get_time = at.get_property("getTime")?;
+        assert_eq!(1_700_000_000_123.0, get_time.call(&at, &[])?.as_f64()?);
+        let to_iso_string = at.get_property("toISOString")?;
+        assert_eq!(
+            "2023-11-14T22:13:20.123Z",
+            to_iso_string.call(&at, &[])?.as_str()?
+        );
+
+        assert_eq!(r#"{"at":1700000000123}"#, serde_json::to_string(&event)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserializes_millis_and_rfc_3339() -> Result<()> {
+        let context = JSContextRef::default();
+        let expected = DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+        for source in [
+            "({at: 1700000000123})",
+            "({at: new Date(1700000000123).getTime()})",
+            "({at: new Date(1700000000123).toISOString()})",
+            "({at: '2023-11-14T23:13:20.123+01:00'})",
+        ] {
+            let val = context.eval_global("main", source)?;
+            let event = Event::deserialize(&mut ValueDeserializer::from(val))?;
+            assert_eq!(expected, event.at, "{source}");
+        }
+
+        let val = context.eval_global("main", "({at: 'yesterday'})")?;
+        assert!(Event::deserialize(&mut ValueDeserializer::from(val)).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Tells an absent property apart from one that is `null`, for `Option<Option<T>>` fields.
+//!
+//! A plain `Option<T>` field deserializes both `{}` and `{ tags: null }` as `None`, which loses
+//! the difference between "leave unchanged" and "clear" in inputs like PATCH requests. With this
+//! adapter, an absent property is `None`, `null` is `Some(None)` and any other value is
+//! `Some(Some(value))`. The field has to be marked `#[serde(default)]` so absent properties are
+//! accepted, and `undefined` counts as `null` unless
+//! [`Deserializer::skip_undefined_properties`] is enabled.
+//!
+//! When serializing, `Some(None)` becomes `null`. Use `skip_serializing_if = "Option::is_none"`
+//! to leave out the property for `None`.
+//!
+//! [`Deserializer::skip_undefined_properties`]: crate::Deserializer::skip_undefined_properties
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Deserialize)]
+//! struct UserPatch {
+//!     #[serde(default, with = "quickjs_wasm_rs::adapters::double_option")]
+//!     tags: Option<Option<Vec<String>>>,
+//! }
+//! ```
+
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+pub fn serialize<S, T>(v: &Option<Option<T>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    match v {
+        Some(Some(v)) => serializer.serialize_some(v),
+        Some(None) | None => serializer.serialize_none(),
+    }
+}
+
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use crate::serialize::ser::Serializer as ValueSerializer;
+    use anyhow::Result;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct UserPatch {
+        #[serde(default, with = "super", skip_serializing_if = "Option::is_none")]
+        tags: Option<Option<Vec<String>>>,
+        #[serde(default)]
+        roles: Option<Vec<String>>,
+    }
+
+    #[test]
+    fn test_absent_null_and_populated() -> Result<()> {
+        let context = JSContextRef::default();
+        for (source, tags, roles) in [
+            ("({})", None, None),
+            ("({tags: null, roles: null})", Some(None), None),
+            (
+                "({tags: ['a'], roles: ['admin']})",
+                Some(Some(vec!["a".to_string()])),
+                Some(vec!["admin".to_string()]),
+            ),
+            ("({tags: []})", Some(Some(vec![])), None),
+        ] {
+            let val = context.eval_global("main", source)?;
+            let patch = UserPatch::deserialize(&mut ValueDeserializer::from(val))?;
+            assert_eq!(UserPatch { tags, roles }, patch, "{source}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_undefined_is_absent_when_skipped() -> Result<()> {
+        let context = JSContextRef::default();
+        let val = context.eval_global("main", "({tags: undefined})")?;
+        let patch = UserPatch::deserialize(&mut ValueDeserializer::from(val))?;
+        assert_eq!(Some(None), patch.tags);
+
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.skip_undefined_properties(true);
+        let patch = UserPatch::deserialize(&mut deserializer)?;
+        assert_eq!(None, patch.tags);
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize() -> Result<()> {
+        let context = JSContextRef::default();
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        UserPatch {
+            tags: Some(None),
+            roles: None,
+        }
+        .serialize(&mut serializer)?;
+        assert!(serializer.value.get_property("tags")?.is_null());
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        UserPatch {
+            tags: None,
+            roles: None,
+        }
+        .serialize(&mut serializer)?;
+        assert!(!serializer.value.has_own_property("tags")?);
+        Ok(())
+    }
+}
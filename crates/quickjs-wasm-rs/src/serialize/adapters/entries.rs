@@ -0,0 +1,119 @@
+//! Maps a `Vec<(K, V)>` to and from an object, using the object's entries.
+//!
+//! Unlike map types, the entries keep the order of the object's properties, which is the order
+//! they were added in for string keys. Since property names are unique, serializing entries with
+//! a repeated key keeps the last value.
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Deserialize)]
+//! struct Headers {
+//!     #[serde(with = "quickjs_wasm_rs::adapters::entries")]
+//!     values: Vec<(String, String)>,
+//! }
+//! ```
+
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{SerializeMap, Serializer};
+use serde::Serialize;
+use std::fmt;
+use std::marker::PhantomData;
+
+pub fn serialize<S, K, V>(entries: &[(K, V)], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize,
+    V: Serialize,
+{
+    let mut map = serializer.serialize_map(Some(entries.len()))?;
+    for (key, value) in entries {
+        map.serialize_entry(key, value)?;
+    }
+    map.end()
+}
+
+pub fn deserialize<'de, D, K, V>(deserializer: D) -> Result<Vec<(K, V)>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    struct EntriesVisitor<K, V>(PhantomData<(K, V)>);
+
+    impl<'de, K, V> Visitor<'de> for EntriesVisitor<K, V>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        type Value = Vec<(K, V)>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an object")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+            while let Some(entry) = map.next_entry()? {
+                entries.push(entry);
+            }
+            Ok(entries)
+        }
+    }
+
+    deserializer.deserialize_map(EntriesVisitor(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use crate::serialize::ser::Serializer as ValueSerializer;
+    use anyhow::Result;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct Scores {
+        #[serde(with = "super")]
+        entries: Vec<(String, i32)>,
+    }
+
+    #[test]
+    fn test_deserializes_object_entries_in_order() -> Result<()> {
+        let context = JSContextRef::default();
+        let val = context.eval_global("main", "({b: 2, a: 1})")?;
+        let entries: Vec<(String, i32)> = super::deserialize(&mut ValueDeserializer::from(val))?;
+        assert_eq!(vec![("b".to_string(), 2), ("a".to_string(), 1)], entries);
+
+        let val = context.eval_global("main", "({entries: {a: 1, b: 2}})")?;
+        let scores = Scores::deserialize(&mut ValueDeserializer::from(val))?;
+        assert_eq!(
+            vec![("a".to_string(), 1), ("b".to_string(), 2)],
+            scores.entries
+        );
+
+        let val = context.eval_global("main", "({entries: [1, 2]})")?;
+        let err = Scores::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert!(err.to_string().contains("expected an object"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<()> {
+        let context = JSContextRef::default();
+        let expected = Scores {
+            entries: vec![("z".to_string(), 26), ("y".to_string(), 25)],
+        };
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        expected.serialize(&mut serializer)?;
+        let object = serializer.value.get_property("entries")?;
+        assert_eq!(26, object.get_property("z")?.as_i32_unchecked());
+
+        let actual = Scores::deserialize(&mut ValueDeserializer::from(serializer.value))?;
+        assert_eq!(expected, actual);
+        Ok(())
+    }
+}
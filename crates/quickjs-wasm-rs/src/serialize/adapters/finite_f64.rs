@@ -0,0 +1,252 @@
+//! Rejects `NaN`, `Infinity` and `-Infinity` for `f64` fields.
+//!
+//! JavaScript numbers can be non-finite, and values like `0 / 0` end up in inputs easily. Fields
+//! using this adapter fail to deserialize from non-finite numbers, and fail to serialize them as
+//! well. To accept non-finite numbers but handle them explicitly, use [`checked`], which maps
+//! them to a [`NonFinite`] error value instead, or the [`MaybeFinite`] field type.
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Deserialize)]
+//! struct Payment {
+//!     #[serde(with = "quickjs_wasm_rs::adapters::finite_f64")]
+//!     amount: f64,
+//! }
+//! ```
+
+use serde::de::{self, Deserialize, Deserializer, Unexpected};
+use serde::ser::{self, Serialize, Serializer};
+use std::fmt;
+
+/// A number that is not finite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonFinite {
+    NaN,
+    Infinity,
+    NegativeInfinity,
+}
+
+impl NonFinite {
+    /// Classifies `v`, returning `Ok(v)` if it's finite.
+    pub fn check(v: f64) -> Result<f64, NonFinite> {
+        if v.is_nan() {
+            Err(NonFinite::NaN)
+        } else if v == f64::INFINITY {
+            Err(NonFinite::Infinity)
+        } else if v == f64::NEG_INFINITY {
+            Err(NonFinite::NegativeInfinity)
+        } else {
+            Ok(v)
+        }
+    }
+
+    /// Returns the number this variant stands for.
+    pub fn to_f64(self) -> f64 {
+        match self {
+            NonFinite::NaN => f64::NAN,
+            NonFinite::Infinity => f64::INFINITY,
+            NonFinite::NegativeInfinity => f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl fmt::Display for NonFinite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NonFinite::NaN => write!(f, "NaN"),
+            NonFinite::Infinity => write!(f, "Infinity"),
+            NonFinite::NegativeInfinity => write!(f, "-Infinity"),
+        }
+    }
+}
+
+/// A number that is `None` when it's not finite, for use as a field type.
+///
+/// `NaN`, `Infinity` and `-Infinity` deserialize as `MaybeFinite(None)`, other numbers as
+/// `MaybeFinite(Some(n))`. `None` serializes as `NaN`.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Deserialize)]
+/// struct Stats {
+///     average: MaybeFinite,
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MaybeFinite(pub Option<f64>);
+
+impl<'de> Deserialize<'de> for MaybeFinite {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        f64::deserialize(deserializer).map(|v| MaybeFinite(NonFinite::check(v).ok()))
+    }
+}
+
+impl Serialize for MaybeFinite {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_f64(self.0.unwrap_or(f64::NAN))
+    }
+}
+
+pub fn serialize<S>(v: &f64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match NonFinite::check(*v) {
+        Ok(v) => serializer.serialize_f64(v),
+        Err(non_finite) => Err(ser::Error::custom(format!(
+            "expected a finite number, found {non_finite}"
+        ))),
+    }
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let v = f64::deserialize(deserializer)?;
+    NonFinite::check(v)
+        .map_err(|_| de::Error::invalid_value(Unexpected::Float(v), &"a finite number"))
+}
+
+/// Deserializes numbers into `Result<f64, NonFinite>`, so non-finite numbers are accepted but
+/// have to be handled explicitly.
+///
+/// # Example
+///
+/// ```
+/// #[derive(Deserialize)]
+/// struct Measurement {
+///     #[serde(with = "quickjs_wasm_rs::adapters::finite_f64::checked")]
+///     ratio: Result<f64, NonFinite>,
+/// }
+/// ```
+pub mod checked {
+    use super::NonFinite;
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::Serializer;
+
+    pub fn serialize<S>(v: &Result<f64, NonFinite>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_f64(v.unwrap_or_else(NonFinite::to_f64))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Result<f64, NonFinite>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        f64::deserialize(deserializer).map(NonFinite::check)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MaybeFinite, NonFinite};
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use crate::serialize::ser::Serializer as ValueSerializer;
+    use anyhow::Result;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Payment {
+        #[serde(with = "super")]
+        amount: f64,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Measurement {
+        #[serde(with = "super::checked")]
+        ratio: Result<f64, NonFinite>,
+    }
+
+    #[test]
+    fn test_finite_numbers() -> Result<()> {
+        let context = JSContextRef::default();
+        let val = context.eval_global("main", "({amount: 12.5})")?;
+        let payment = Payment::deserialize(&mut ValueDeserializer::from(val))?;
+        assert_eq!(Payment { amount: 12.5 }, payment);
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        payment.serialize(&mut serializer)?;
+        assert_eq!(12.5, serializer.value.get_property("amount")?.as_f64()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_non_finite_numbers_are_rejected() -> Result<()> {
+        let context = JSContextRef::default();
+        for source in ["({amount: NaN})", "({amount: 1 / 0})", "({amount: -1 / 0})"] {
+            let val = context.eval_global("main", source)?;
+            let err = Payment::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+            assert!(
+                err.to_string().contains("expected a finite number"),
+                "{source}: {err}"
+            );
+        }
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        let payment = Payment {
+            amount: f64::INFINITY,
+        };
+        assert!(payment.serialize(&mut serializer).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_checked() -> Result<()> {
+        let context = JSContextRef::default();
+        for (source, expected) in [
+            ("({ratio: 0.5})", Ok(0.5)),
+            ("({ratio: NaN})", Err(NonFinite::NaN)),
+            ("({ratio: 1 / 0})", Err(NonFinite::Infinity)),
+            ("({ratio: -1 / 0})", Err(NonFinite::NegativeInfinity)),
+        ] {
+            let val = context.eval_global("main", source)?;
+            let measurement = Measurement::deserialize(&mut ValueDeserializer::from(val))?;
+            assert_eq!(Measurement { ratio: expected }, measurement, "{source}");
+        }
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        Measurement {
+            ratio: Err(NonFinite::NegativeInfinity),
+        }
+        .serialize(&mut serializer)?;
+        let ratio = serializer.value.get_property("ratio")?.as_f64()?;
+        assert_eq!(f64::NEG_INFINITY, ratio);
+        Ok(())
+    }
+
+    #[test]
+    fn test_maybe_finite() -> Result<()> {
+        let context = JSContextRef::default();
+        for (source, expected) in [
+            ("0.25", Some(0.25)),
+            ("-3", Some(-3.0)),
+            ("NaN", None),
+            ("1 / 0", None),
+            ("-1 / 0", None),
+        ] {
+            let val = context.eval_global("main", source)?;
+            let actual = MaybeFinite::deserialize(&mut ValueDeserializer::from(val))?;
+            assert_eq!(MaybeFinite(expected), actual, "{source}");
+        }
+
+        let val = context.eval_global("main", "'1'")?;
+        assert!(MaybeFinite::deserialize(&mut ValueDeserializer::from(val)).is_err());
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        MaybeFinite(None).serialize(&mut serializer)?;
+        assert!(serializer.value.as_f64()?.is_nan());
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+//! Serializes an `IpAddr` as an array of its octets, like `[127, 0, 0, 1]`.
+//!
+//! Addresses are serialized as strings like `"127.0.0.1"` without the adapter. Some JavaScript
+//! libraries represent them as byte arrays instead, 4 bytes long for IPv4 and 16 for IPv6, which
+//! is what this adapter reads and writes.
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Serialize, Deserialize)]
+//! struct Peer {
+//!     #[serde(with = "quickjs_wasm_rs::adapters::ip_octets")]
+//!     address: IpAddr,
+//! }
+//! ```
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+pub fn serialize<S>(address: &IpAddr, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match address {
+        IpAddr::V4(address) => address.octets().serialize(serializer),
+        IpAddr::V6(address) => address.octets().serialize(serializer),
+    }
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<IpAddr, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let octets = Vec::<u8>::deserialize(deserializer)?;
+    if let Ok(octets) = <[u8; 4]>::try_from(octets.as_slice()) {
+        Ok(Ipv4Addr::from(octets).into())
+    } else if let Ok(octets) = <[u8; 16]>::try_from(octets.as_slice()) {
+        Ok(Ipv6Addr::from(octets).into())
+    } else {
+        Err(de::Error::invalid_length(
+            octets.len(),
+            &"4 octets for IPv4 or 16 for IPv6",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use crate::serialize::ser::Serializer as ValueSerializer;
+    use anyhow::Result;
+    use serde::{Deserialize, Serialize};
+    use std::net::IpAddr;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Peer {
+        #[serde(with = "super")]
+        address: IpAddr,
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<()> {
+        let context = JSContextRef::default();
+        for (address, length) in [("192.168.0.1", 4), ("fe80::1", 16)] {
+            let peer = Peer {
+                address: address.parse()?,
+            };
+            let mut serializer = ValueSerializer::from_context(&context)?;
+            peer.serialize(&mut serializer)?;
+            let octets = serializer.value.get_property("address")?;
+            assert_eq!(length, octets.as_array_vec()?.len(), "{address}");
+
+            let actual = Peer::deserialize(&mut ValueDeserializer::from(serializer.value))?;
+            assert_eq!(peer, actual);
+        }
+
+        let val = context.eval_global("main", "({address: [127, 0, 0, 1]})")?;
+        let peer = Peer::deserialize(&mut ValueDeserializer::from(val))?;
+        assert_eq!("127.0.0.1".parse::<IpAddr>()?, peer.address);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_length() -> Result<()> {
+        let context = JSContextRef::default();
+        let val = context.eval_global("main", "({address: [127, 0, 1]})")?;
+        let err = Peer::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert!(err.to_string().contains("invalid length 3"), "{err}");
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+//! Adapters for use with `#[serde(with = "...")]` that map Rust types to shapes commonly used by
+//! JavaScript code.
+
+#[cfg(feature = "bytes")]
+pub mod bytes;
+#[cfg(feature = "chrono")]
+pub mod date_time;
+pub mod double_option;
+pub mod entries;
+pub mod finite_f64;
+pub mod ip_octets;
+pub mod negative_zero;
+pub mod null_as_default;
+pub mod raw_number;
+pub mod raw_value;
+pub mod result_envelope;
+#[cfg(feature = "uuid")]
+pub mod uuid;
@@ -0,0 +1,105 @@
+//! Keeps `-0` distinct from `0` for `f64` fields.
+//!
+//! Numbers that are whole are deserialized as integers, which turns `-0` into `0`. Fields using
+//! this adapter get `-0.0` for `-0` instead, and otherwise deserialize like any `f64`. `-0.0` is
+//! serialized as `-0`, which is also the case without the adapter.
+//!
+//! With deserializers other than this crate's, fields deserialize like a plain `f64`.
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Deserialize)]
+//! struct Reading {
+//!     #[serde(with = "quickjs_wasm_rs::adapters::negative_zero")]
+//!     celsius: f64,
+//! }
+//! ```
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::Serializer;
+use std::fmt;
+
+/// The name `Deserializer::deserialize_newtype_struct` looks for to visit `-0` as an `f64`.
+pub(crate) const TOKEN: &str = "$quickjs_wasm_rs::NegativeZero";
+
+pub fn serialize<S>(v: &f64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_f64(*v)
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct NegativeZeroVisitor;
+
+    impl<'de> Visitor<'de> for NegativeZeroVisitor {
+        type Value = f64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a number")
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<f64, E> {
+            Ok(v)
+        }
+
+        fn visit_newtype_struct<D>(self, deserializer: D) -> Result<f64, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            f64::deserialize(deserializer)
+        }
+    }
+
+    deserializer.deserialize_newtype_struct(TOKEN, NegativeZeroVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use crate::serialize::ser::Serializer as ValueSerializer;
+    use anyhow::Result;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize, Debug)]
+    struct Reading {
+        #[serde(with = "super")]
+        celsius: f64,
+        plain: f64,
+    }
+
+    fn deserialize(context: &JSContextRef, source: &str) -> Result<Reading> {
+        let val = context.eval_global("main", source)?;
+        Ok(Reading::deserialize(&mut ValueDeserializer::from(val))?)
+    }
+
+    #[test]
+    fn test_negative_zero() -> Result<()> {
+        let context = JSContextRef::default();
+        let reading = deserialize(&context, "({celsius: -0, plain: -0})")?;
+        assert_eq!(0.0, reading.celsius);
+        assert!(reading.celsius.is_sign_negative());
+        assert!(reading.plain.is_sign_positive());
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        reading.serialize(&mut serializer)?;
+        assert!(serializer.value.get_property("celsius")?.is_negative_zero());
+        Ok(())
+    }
+
+    #[test]
+    fn test_other_numbers() -> Result<()> {
+        let context = JSContextRef::default();
+        for (source, expected) in [("0", 0.0_f64), ("3", 3.0), ("-2.5", -2.5)] {
+            let reading = deserialize(&context, &format!("({{celsius: {source}, plain: 0}})"))?;
+            assert_eq!(expected.to_bits(), reading.celsius.to_bits(), "{source}");
+        }
+        assert!(deserialize(&context, "({celsius: '-0', plain: 0})").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+//! Deserializes `null` and `undefined` elements of an array as the element type's default value.
+//!
+//! For lenient inputs like `[1, null, 3]`, a `Vec<i32>` using this adapter gets `[1, 0, 3]`. To
+//! drop the `null` elements instead, see [`Deserializer::skip_null_elements`]. Serializing is
+//! the same as without the adapter.
+//!
+//! [`Deserializer::skip_null_elements`]: crate::Deserializer::skip_null_elements
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Deserialize)]
+//! struct Readings {
+//!     #[serde(with = "quickjs_wasm_rs::adapters::null_as_default")]
+//!     values: Vec<i32>,
+//! }
+//! ```
+
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+pub fn serialize<S, T>(elements: &[T], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    elements.serialize(serializer)
+}
+
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    let elements = Vec::<Option<T>>::deserialize(deserializer)?;
+    Ok(elements
+        .into_iter()
+        .map(Option::unwrap_or_default)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use anyhow::Result;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Readings {
+        #[serde(with = "super")]
+        values: Vec<i32>,
+        #[serde(with = "super")]
+        labels: Vec<String>,
+    }
+
+    #[test]
+    fn test_null_elements_use_the_default() -> Result<()> {
+        let context = JSContextRef::default();
+        let val = context.eval_global(
+            "main",
+            "({values: [1, null, 3, undefined], labels: ['a', null]})",
+        )?;
+        let readings = Readings::deserialize(&mut ValueDeserializer::from(val))?;
+        assert_eq!(
+            Readings {
+                values: vec![1, 0, 3, 0],
+                labels: vec!["a".to_string(), String::new()],
+            },
+            readings
+        );
+
+        let val = context.eval_global("main", "({values: [1, 'two'], labels: []})")?;
+        assert!(Readings::deserialize(&mut ValueDeserializer::from(val)).is_err());
+        Ok(())
+    }
+}
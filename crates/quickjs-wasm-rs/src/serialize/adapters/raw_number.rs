@@ -0,0 +1,148 @@
+//! Keeps numbers as their exact JavaScript string representation.
+//!
+//! Deserializing a number into `f64` and formatting it again can change how it's written, and
+//! `BigInt`s outside the range of `i64` and `u64` can't be deserialized at all. [`RawNumber`]
+//! instead holds the text JavaScript's `String(n)` produces for a number or a `BigInt`, which is
+//! useful for audit logs that have to record inputs exactly.
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Deserialize)]
+//! struct Transfer {
+//!     amount: RawNumber,
+//! }
+//! ```
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+
+/// The name `Deserializer::deserialize_newtype_struct` looks for to produce a number's string.
+pub(crate) const TOKEN: &str = "$quickjs_wasm_rs::RawNumber";
+
+/// A number or `BigInt` in the form JavaScript converts it to a string, like `"12.5"`, `"1e+21"`
+/// or `"18446744073709551616"`.
+///
+/// Strings and other values fail to deserialize. With deserializers other than this crate's,
+/// numbers are formatted by Rust instead. `RawNumber` serializes as a string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawNumber(pub String);
+
+impl RawNumber {
+    /// Returns the number's text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for RawNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for RawNumber {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for RawNumber {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RawNumberVisitor;
+
+        impl<'de> Visitor<'de> for RawNumberVisitor {
+            type Value = RawNumber;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a number")
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<RawNumber, E> {
+                Ok(RawNumber(v.to_string()))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<RawNumber, E> {
+                Ok(RawNumber(v.to_string()))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<RawNumber, E> {
+                Ok(RawNumber(v.to_string()))
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<RawNumber, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                String::deserialize(deserializer).map(RawNumber)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(TOKEN, RawNumberVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RawNumber;
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use anyhow::Result;
+    use serde::Deserialize;
+
+    #[test]
+    fn test_numbers_keep_their_javascript_text() -> Result<()> {
+        let context = JSContextRef::default();
+        for (source, expected) in [
+            ("42", "42"),
+            ("-7", "-7"),
+            ("0.1 + 0.2", "0.30000000000000004"),
+            ("12.50", "12.5"),
+            ("1e21", "1e+21"),
+            ("2n ** 64n", "18446744073709551616"),
+            ("-(2n ** 70n)", "-1180591620717411303424"),
+        ] {
+            let val = context.eval_global("main", source)?;
+            let raw = RawNumber::deserialize(&mut ValueDeserializer::from(val))?;
+            assert_eq!(expected, raw.as_str(), "{source}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_non_numbers_are_rejected() -> Result<()> {
+        let context = JSContextRef::default();
+        for source in ["'42'", "null", "({})"] {
+            let val = context.eval_global("main", source)?;
+            let err = RawNumber::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+            assert!(
+                err.to_string().contains("expected a number"),
+                "{source}: {err}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_field() -> Result<()> {
+        #[derive(Deserialize)]
+        struct Transfer {
+            amount: RawNumber,
+            fee: Option<RawNumber>,
+        }
+
+        let context = JSContextRef::default();
+        let val = context.eval_global("main", "({amount: 1234.5, fee: null})")?;
+        let transfer = Transfer::deserialize(&mut ValueDeserializer::from(val))?;
+        assert_eq!("1234.5", transfer.amount.to_string());
+        assert_eq!(None, transfer.fee);
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+//! Captures JavaScript values as they are, to be inspected later.
+//!
+//! [`RawValue`] is useful for parts of the input whose type isn't known up front, like the
+//! elements of a mixed-type array or an opaque `payload` field: they deserialize into a
+//! [`JSValueRef`] which the host can then handle with the value's own methods or deserialize
+//! again once it knows the type.
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Deserialize)]
+//! struct Message<'a> {
+//!     kind: String,
+//!     #[serde(borrow)]
+//!     payload: RawValue<'a>,
+//! }
+//! ```
+
+use crate::js_binding::value::JSValueRef;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use std::cell::Cell;
+use std::fmt;
+use std::ptr;
+
+/// The name `Deserializer::deserialize_newtype_struct` looks for to hand over the current value.
+pub(crate) const TOKEN: &str = "$quickjs_wasm_rs::RawValue";
+
+thread_local! {
+    /// The value being deserialized into a `RawValue`, set by `Deserializer` for the duration of
+    /// the visitor call since visitors can't be given a `JSValueRef` directly.
+    static CURRENT: Cell<*const ()> = const { Cell::new(ptr::null()) };
+}
+
+/// Runs `f` with `value` available to the `RawValue` visitor.
+pub(crate) fn with_current<T>(value: &JSValueRef, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT.with(|current| current.replace(value as *const JSValueRef as *const ()));
+    let result = f();
+    CURRENT.with(|current| current.set(previous));
+    result
+}
+
+/// A JavaScript value deserialized without converting it to a Rust type.
+///
+/// The value refers to the same JavaScript value as the input, so objects aren't copied and
+/// changes to them are visible through both. Only [`Deserializer`](crate::Deserializer) can
+/// produce a `RawValue`, other deserializers fail with an error.
+#[derive(Debug, Clone, Copy)]
+pub struct RawValue<'a>(pub JSValueRef<'a>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for RawValue<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        const UNSUPPORTED: &str =
+            "RawValue can only be deserialized with quickjs_wasm_rs::Deserializer";
+
+        struct RawValueVisitor;
+
+        impl<'de> Visitor<'de> for RawValueVisitor {
+            type Value = RawValue<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a JavaScript value")
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<RawValue<'de>, E> {
+                let current = CURRENT.with(|current| current.replace(ptr::null()));
+                if current.is_null() {
+                    return Err(E::custom(UNSUPPORTED));
+                }
+                // SAFETY: `with_current` only sets the pointer while the value it points to,
+                // which lives for `'de`, is being deserialized.
+                Ok(RawValue(unsafe { *(current as *const JSValueRef<'de>) }))
+            }
+
+            fn visit_newtype_struct<D>(self, _deserializer: D) -> Result<RawValue<'de>, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                Err(de::Error::custom(UNSUPPORTED))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(TOKEN, RawValueVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RawValue;
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use anyhow::Result;
+    use serde::Deserialize;
+
+    #[test]
+    fn test_mixed_array() -> Result<()> {
+        let context = JSContextRef::default();
+        let val = context.eval_global("main", "[1, 'a', {b: true}, null]")?;
+        let values = Vec::<RawValue>::deserialize(&mut ValueDeserializer::from(val))?;
+        assert_eq!(4, values.len());
+        assert_eq!(1, values[0].0.as_i32_unchecked());
+        assert_eq!("a", values[1].0.as_str()?);
+        assert!(values[2].0.get_property("b")?.as_bool()?);
+        assert!(values[3].0.is_null());
+
+        // The raw value is the original object, not a copy.
+        values[2].0.set_property("c", context.value_from_i32(1)?)?;
+        let c = val.get_indexed_property(2)?.get_property("c")?;
+        assert_eq!(1, c.as_i32_unchecked());
+        Ok(())
+    }
+
+    #[test]
+    fn test_field() -> Result<()> {
+        #[derive(Deserialize)]
+        struct Message<'a> {
+            kind: String,
+            #[serde(borrow)]
+            payload: RawValue<'a>,
+        }
+
+        let context = JSContextRef::default();
+        let val = context.eval_global("main", "({kind: 'point', payload: [1, 2]})")?;
+        let message = Message::deserialize(&mut ValueDeserializer::from(val))?;
+        assert_eq!("point", message.kind);
+        let point = Vec::<i32>::deserialize(&mut ValueDeserializer::from(message.payload.0))?;
+        assert_eq!(vec![1, 2], point);
+        Ok(())
+    }
+
+    #[test]
+    fn test_other_deserializers_fail() {
+        let err = serde_json::from_str::<RawValue>("null").unwrap_err();
+        assert!(
+            err.to_string().contains("quickjs_wasm_rs::Deserializer"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Maps a `Result<T, E>` to and from an envelope object.
+//!
+//! `Ok(value)` corresponds to `{ ok: true, value }` and `Err(error)` to `{ ok: false, error }`,
+//! which is a common shape for values returned by RPC-style guests. When `T` can be deserialized
+//! from `undefined`, like `()` or an `Option`, the `value` property can be left out.
+//!
+//! Envelopes that use a string discriminant instead, like `{ status: "ok", value }`, don't need
+//! this adapter since they can be deserialized into an internally tagged enum with
+//! `#[serde(tag = "status")]`.
+//!
+//! `#[serde(with = "...")]` only applies to fields. To serialize a `Result` that isn't a field,
+//! like the return value of a guest function, wrap it in [`ResultEnvelope`].
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Deserialize)]
+//! struct Response {
+//!     #[serde(with = "quickjs_wasm_rs::adapters::result_envelope")]
+//!     result: Result<u32, String>,
+//! }
+//! ```
+
+use serde::de::{self, Deserialize, Deserializer, IntoDeserializer};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+
+/// A `Result` that serializes to and deserializes from an envelope object.
+///
+/// # Example
+///
+/// ```
+/// let result: Result<u32, String> = Ok(1);
+/// ResultEnvelope(result).serialize(&mut serializer)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultEnvelope<T, E>(pub Result<T, E>);
+
+impl<T: Serialize, E: Serialize> Serialize for ResultEnvelope<T, E> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, E: Deserialize<'de>> Deserialize<'de> for ResultEnvelope<T, E> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize(deserializer).map(ResultEnvelope)
+    }
+}
+
+pub fn serialize<S, T, E>(result: &Result<T, E>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+    E: Serialize,
+{
+    let mut envelope = serializer.serialize_struct("ResultEnvelope", 2)?;
+    match result {
+        Ok(value) => {
+            envelope.serialize_field("ok", &true)?;
+            envelope.serialize_field("value", value)?;
+        }
+        Err(error) => {
+            envelope.serialize_field("ok", &false)?;
+            envelope.serialize_field("error", error)?;
+        }
+    }
+    envelope.end()
+}
+
+pub fn deserialize<'de, D, T, E>(deserializer: D) -> Result<Result<T, E>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+    E: Deserialize<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(bound = "T: Deserialize<'de>, E: Deserialize<'de>")]
+    struct Envelope<T, E> {
+        ok: bool,
+        value: Option<T>,
+        error: Option<E>,
+    }
+
+    let envelope = Envelope::<T, E>::deserialize(deserializer)?;
+    match (envelope.ok, envelope.value, envelope.error) {
+        (true, Some(value), _) => Ok(Ok(value)),
+        (true, None, _) => T::deserialize(().into_deserializer())
+            .map(Ok)
+            .map_err(|_: de::value::Error| de::Error::missing_field("value")),
+        (false, _, Some(error)) => Ok(Err(error)),
+        (false, _, None) => Err(de::Error::missing_field("error")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResultEnvelope;
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use crate::serialize::ser::Serializer as ValueSerializer;
+    use anyhow::Result;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct Response {
+        #[serde(with = "super")]
+        result: std::result::Result<Vec<u32>, Failure>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct Failure {
+        code: u32,
+        message: String,
+    }
+
+    fn deserialize<T: for<'de> Deserialize<'de>>(
+        context: &JSContextRef,
+        source: &str,
+    ) -> Result<T> {
+        let val = context.eval_global("main", source)?;
+        Ok(T::deserialize(&mut ValueDeserializer::from(val))?)
+    }
+
+    #[test]
+    fn test_ok_and_error_envelopes() -> Result<()> {
+        let context = JSContextRef::default();
+
+        let ok: Response = deserialize(&context, "({result: {ok: true, value: [1, 2]}})")?;
+        assert_eq!(Ok(vec![1, 2]), ok.result);
+
+        let err: Response = deserialize(
+            &context,
+            "({result: {ok: false, error: {code: 404, message: 'not found'}}})",
+        )?;
+        assert_eq!(
+            Err(Failure {
+                code: 404,
+                message: "not found".to_string()
+            }),
+            err.result
+        );
+
+        let missing = deserialize::<Response>(&context, "({result: {ok: false}})").unwrap_err();
+        assert_eq!("missing field `error`", missing.to_string());
+        let missing = deserialize::<Response>(&context, "({result: {ok: true}})").unwrap_err();
+        assert_eq!("missing field `value`", missing.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_can_be_left_out_for_unit() -> Result<()> {
+        #[derive(Deserialize)]
+        struct Ack {
+            #[serde(with = "super")]
+            result: std::result::Result<(), String>,
+        }
+
+        let context = JSContextRef::default();
+        let ack: Ack = deserialize(&context, "({result: {ok: true}})")?;
+        assert_eq!(Ok(()), ack.result);
+        Ok(())
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<()> {
+        let context = JSContextRef::default();
+        for expected in [
+            Response {
+                result: Ok(vec![3]),
+            },
+            Response {
+                result: Err(Failure {
+                    code: 500,
+                    message: "oops".to_string(),
+                }),
+            },
+        ] {
+            let mut serializer = ValueSerializer::from_context(&context)?;
+            expected.serialize(&mut serializer)?;
+            let envelope = serializer.value.get_property("result")?;
+            assert_eq!(
+                expected.result.is_ok(),
+                envelope.get_property("ok")?.as_bool()?
+            );
+
+            let actual = Response::deserialize(&mut ValueDeserializer::from(serializer.value))?;
+            assert_eq!(expected, actual);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_result_envelope_roundtrip() -> Result<()> {
+        type Envelope = ResultEnvelope<u32, String>;
+
+        let context = JSContextRef::default();
+        for expected in [
+            ResultEnvelope(Ok(7)),
+            ResultEnvelope(Err("denied".to_string())),
+        ] {
+            let mut serializer = ValueSerializer::from_context(&context)?;
+            expected.serialize(&mut serializer)?;
+            let envelope = serializer.value;
+            assert_eq!(expected.0.is_ok(), envelope.get_property("ok")?.as_bool()?);
+            assert_eq!(expected.0.is_ok(), envelope.has_own_property("value")?);
+            assert_eq!(expected.0.is_err(), envelope.has_own_property("error")?);
+
+            let actual = Envelope::deserialize(&mut ValueDeserializer::from(envelope))?;
+            assert_eq!(expected, actual);
+        }
+
+        let ok: Envelope = deserialize(&context, "({ok: true, value: 1})")?;
+        assert_eq!(ResultEnvelope(Ok(1)), ok);
+        Ok(())
+    }
+
+    #[test]
+    fn test_internally_tagged_envelope() -> Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(tag = "status", rename_all = "lowercase")]
+        enum Outcome {
+            Ok { value: u32 },
+            Error { error: String },
+        }
+
+        let context = JSContextRef::default();
+        assert_eq!(
+            Outcome::Ok { value: 1 },
+            deserialize(&context, "({status: 'ok', value: 1})")?
+        );
+        assert_eq!(
+            Outcome::Error {
+                error: "denied".to_string()
+            },
+            deserialize(&context, "({status: 'error', error: 'denied'})")?
+        );
+        Ok(())
+    }
+}
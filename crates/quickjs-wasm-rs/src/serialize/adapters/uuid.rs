@@ -0,0 +1,153 @@
+//! Maps a `uuid::Uuid` to and from a string, or from 16 bytes.
+//!
+//! UUIDs are serialized as hyphenated strings like `"67e55044-10b1-426f-9247-bb680e5fe0c8"`. They
+//! are deserialized from strings in any format `Uuid::parse_str` accepts, or from 16 bytes given
+//! as an array of numbers, a `Uint8Array` or an `ArrayBuffer`.
+//!
+//! Only available with the `uuid` feature.
+//!
+//! # Example
+//!
+//! ```
+//! #[derive(Deserialize)]
+//! struct Session {
+//!     #[serde(with = "quickjs_wasm_rs::adapters::uuid")]
+//!     id: Uuid,
+//! }
+//! ```
+
+use ::uuid::Uuid;
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::Serializer;
+use std::fmt;
+
+pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_str(uuid.as_hyphenated())
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct UuidVisitor;
+
+    impl<'de> Visitor<'de> for UuidVisitor {
+        type Value = Uuid;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a UUID string or 16 bytes")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Uuid::parse_str(v).map_err(|err| E::custom(format!("invalid UUID `{v}`: {err}")))
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Uuid::from_slice(v).map_err(|_| E::invalid_length(v.len(), &self))
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = [0; 16];
+            for (len, byte) in bytes.iter_mut().enumerate() {
+                *byte = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(len, &self))?;
+            }
+            let mut len = bytes.len();
+            while seq.next_element::<de::IgnoredAny>()?.is_some() {
+                len += 1;
+            }
+            if len != bytes.len() {
+                return Err(de::Error::invalid_length(len, &self));
+            }
+            Ok(Uuid::from_bytes(bytes))
+        }
+    }
+
+    // Typed arrays are only deserialized as sequences when one is asked for, and strings are
+    // still visited as strings.
+    deserializer.deserialize_seq(UuidVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use crate::serialize::ser::Serializer as ValueSerializer;
+    use ::uuid::Uuid;
+    use anyhow::Result;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    struct Session {
+        #[serde(with = "super")]
+        id: Uuid,
+    }
+
+    const ID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+    const BYTES: &str = "[0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f, \
+                         0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0, 0xc8]";
+
+    fn deserialize(context: &JSContextRef, source: &str) -> Result<Session> {
+        let val = context.eval_global("main", source)?;
+        Ok(Session::deserialize(&mut ValueDeserializer::from(val))?)
+    }
+
+    #[test]
+    fn test_strings_and_bytes() -> Result<()> {
+        let context = JSContextRef::default();
+        let expected = Session {
+            id: Uuid::parse_str(ID)?,
+        };
+        for source in [
+            format!("({{id: '{ID}'}})"),
+            format!("({{id: '{}'}})", ID.to_uppercase()),
+            format!("({{id: {BYTES}}})"),
+            format!("({{id: new Uint8Array({BYTES})}})"),
+            format!("({{id: new Uint8Array({BYTES}).buffer}})"),
+        ] {
+            assert_eq!(expected, deserialize(&context, &source)?, "{source}");
+        }
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        expected.serialize(&mut serializer)?;
+        assert_eq!(ID, serializer.value.get_property("id")?.as_str()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_uuids() -> Result<()> {
+        let context = JSContextRef::default();
+        let err = deserialize(&context, "({id: 'not-a-uuid'})").unwrap_err();
+        assert!(
+            err.to_string().starts_with("invalid UUID `not-a-uuid`"),
+            "{err}"
+        );
+
+        for (source, len) in [
+            ("({id: [1, 2, 3]})", 3),
+            ("({id: new Uint8Array(17)})", 17),
+            ("({id: new ArrayBuffer(15)})", 15),
+        ] {
+            let err = deserialize(&context, source).unwrap_err();
+            assert_eq!(
+                format!("invalid length {len}, expected a UUID string or 16 bytes"),
+                err.to_string(),
+                "{source}"
+            );
+        }
+
+        assert!(deserialize(
+            &context,
+            "({id: [256, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]})"
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -2,15 +2,24 @@ use crate::js_binding::constants::{MAX_SAFE_INTEGER, MIN_SAFE_INTEGER};
 use crate::js_binding::{properties::Properties, value::BigInt, value::JSValueRef};
 use crate::serialize::err::{Error, Result};
 use anyhow::anyhow;
+use quickjs_wasm_sys::{JSAtom, JSValue as JSValueRaw};
 use serde::de::{self, Error as SerError};
 use serde::forward_to_deserialize_any;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 
+use super::adapters::{negative_zero, raw_number, raw_value};
 use super::as_key;
+use super::schema::Schema;
 
 impl SerError for Error {
     fn custom<T: std::fmt::Display>(msg: T) -> Self {
         Error::Custom(anyhow!(msg.to_string()))
     }
+
+    fn invalid_type(unexp: de::Unexpected, exp: &dyn de::Expected) -> Self {
+        Error::TypeMismatch(format!("invalid type: {unexp}, expected {exp}"))
+    }
 }
 
 /// `Deserializer` is a deserializer for `JSValueRef` values, implementing the `serde::Deserializer` trait.
@@ -31,43 +40,441 @@ impl SerError for Error {
 /// // Use deserializer to deserialize the JavaScript value into a Rust type
 /// let number: i32 = serde::Deserialize::deserialize(deserializer)?;
 /// ```
+///
+/// # Untagged enums
+///
+/// `#[serde(untagged)]` enums are supported, which covers unions where the variant is implied by
+/// which key is present, like `{ circle: { radius } }` and `{ square: { side } }`:
+///
+/// ```
+/// #[derive(Deserialize)]
+/// #[serde(untagged)]
+/// enum Shape {
+///     Circle { circle: Circle },
+///     Square { square: Square },
+/// }
+/// ```
+///
+/// Serde buffers the whole value before trying each variant, so the first matching variant is
+/// used and errors don't say why the other variants didn't match.
+///
+/// # Tracing
+///
+/// With the `tracing` feature, deserializing emits `trace` events for every value visited and
+/// every object key or string `Map` key read, inside a `nested` span for each object and array. A
+/// `debug` event is emitted with the error each time a failure passes through a nested object or
+/// array, so the events show where in the input deserializing failed. None of this is compiled in
+/// without the feature.
 pub struct Deserializer<'de> {
     value: JSValueRef<'de>,
-    map_key: bool,
+    truthy_bools: bool,
+    array_like_objects: bool,
+    skip_null_elements: bool,
+    skip_undefined_properties: bool,
+    trim_strings: bool,
+    normalize_string_values: Option<Case>,
+    normalize_enum_tags: bool,
+    max_iterator_len: usize,
+    max_nodes: usize,
+    /// Values deserialized so far, counted against `max_nodes`.
+    nodes: usize,
+    /// Objects and arrays currently being deserialized, used to detect circular references.
+    ancestors: Vec<JSValueRaw>,
+    allowed_keys: Option<KeyAllowlist>,
+    /// Keys converted to strings so far, reused across objects by
+    /// [`Deserializer::deserialize_rows`] and [`Deserializer::intern_keys`].
+    key_cache: Option<HashMap<JSAtom, String>>,
+    /// Keys of the top-level object, recorded with [`Deserializer::record_seen_keys`].
+    seen_keys: Option<HashSet<String>>,
+    hook: Option<Box<Hook<'de>>>,
+}
+
+/// The default for [`Deserializer::max_iterator_len`].
+const DEFAULT_MAX_ITERATOR_LEN: usize = 1 << 20;
+
+/// A function consulted by [`Deserializer::hook`].
+type Hook<'de> = dyn Fn(&JSValueRef<'de>) -> Option<HookAction<'de>> + 'de;
+
+/// What a [`Deserializer::hook`] does instead of the generic deserialization of a value.
+pub enum HookAction<'de> {
+    /// Deserializes another value in place of the original one, for example the result of
+    /// calling its `valueOf` method.
+    Value(JSValueRef<'de>),
+    /// Visits a string in place of the original value.
+    String(String),
+    /// Visits a number in place of the original value.
+    Number(f64),
+}
+
+/// Object keys accepted by a deserializer configured with [`Deserializer::allowed_keys`].
+struct KeyAllowlist {
+    keys: HashSet<String>,
+    disallowed: DisallowedKeys,
+}
+
+impl KeyAllowlist {
+    /// Checks `key` against the allowlist, returning `false` if it should be skipped.
+    fn allows(&self, key: &str) -> Result<bool> {
+        if self.keys.contains(key) {
+            Ok(true)
+        } else if self.disallowed == DisallowedKeys::Skip {
+            Ok(false)
+        } else {
+            Err(Error::Custom(anyhow!("Key `{key}` is not allowed")))
+        }
+    }
+}
+
+/// How [`Deserializer::allowed_keys`] handles object keys that aren't allowed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisallowedKeys {
+    /// Fail with an error naming the key.
+    Error,
+    /// Leave the key and its value out.
+    Skip,
+}
+
+/// The case [`Deserializer::normalize_string_values`] converts strings to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Case {
+    /// Converts strings with `str::to_lowercase`.
+    Lower,
+    /// Converts strings with `str::to_uppercase`.
+    Upper,
+}
+
+impl Case {
+    fn apply(self, val: &str) -> String {
+        match self {
+            Case::Lower => val.to_lowercase(),
+            Case::Upper => val.to_uppercase(),
+        }
+    }
 }
 
 impl<'de> From<JSValueRef<'de>> for Deserializer<'de> {
     fn from(value: JSValueRef<'de>) -> Self {
         Self {
             value,
-            map_key: false,
+            truthy_bools: false,
+            array_like_objects: false,
+            skip_null_elements: false,
+            skip_undefined_properties: false,
+            trim_strings: false,
+            normalize_string_values: None,
+            normalize_enum_tags: false,
+            max_iterator_len: DEFAULT_MAX_ITERATOR_LEN,
+            max_nodes: usize::MAX,
+            nodes: 0,
+            ancestors: Vec::new(),
+            allowed_keys: None,
+            key_cache: None,
+            seen_keys: None,
+            hook: None,
         }
     }
 }
-impl Deserializer<'_> {
-    fn deserialize_number<'de, V>(&mut self, visitor: V) -> Result<V::Value>
+
+impl<'de> Deserializer<'de> {
+    /// Deserializes an array of objects that share the same shape, like the rows of a table, into
+    /// a `Vec<T>`.
+    ///
+    /// This behaves like deserializing a `Vec<T>` but converts each distinct object key to a
+    /// string only once for the whole array instead of once per row, which adds up for wide and
+    /// long arrays of records.
+    pub fn deserialize_rows<T>(&mut self) -> Result<Vec<T>>
+    where
+        T: de::Deserialize<'de>,
+    {
+        if !self.value.is_array() {
+            return Err(Error::TypeMismatch(format!(
+                "expected an array, found {}",
+                type_name(&self.value)
+            )));
+        }
+
+        let rows = self.value;
+        let length = rows.get_property("length")?.as_u32_unchecked();
+        // Keys interned with `intern_keys` are kept, otherwise the cache only lives for this call.
+        let temporary_cache = self.key_cache.is_none();
+        if temporary_cache {
+            self.key_cache = Some(HashMap::new());
+        }
+        let result = self.nested(|de| {
+            (0..length)
+                .map(|index| {
+                    de.enter(rows.get_indexed_property(index)?)?;
+                    T::deserialize(&mut *de)
+                })
+                .collect()
+        });
+        if temporary_cache {
+            self.key_cache = None;
+        }
+        self.value = rows;
+        result
+    }
+
+    /// Moves on to `value`, a nested value of the current one, counting it against `max_nodes`.
+    fn enter(&mut self, value: JSValueRef<'de>) -> Result<()> {
+        if self.nodes == self.max_nodes {
+            return Err(Error::Custom(anyhow!(
+                "more than {} values to deserialize",
+                self.max_nodes
+            )));
+        }
+        self.nodes += 1;
+        #[cfg(feature = "tracing")]
+        tracing::trace!(kind = type_name(&value), "visiting value");
+        self.value = value;
+        Ok(())
+    }
+
+    /// Visits a string value, converting its case first with `normalize_string_values`.
+    fn visit_string_value<V>(&self, val: &str, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        if self.value.is_repr_as_i32() {
-            return visitor.visit_i32(self.value.as_i32_unchecked());
+        match self.normalize_string_values {
+            Some(case) => visitor.visit_string(case.apply(val)),
+            None => visitor.visit_str(val),
         }
+    }
 
-        if self.value.is_repr_as_f64() {
-            let f64_representation = self.value.as_f64_unchecked();
-            let is_positive = f64_representation.is_sign_positive();
-            let safe_integer_range = (MIN_SAFE_INTEGER as f64)..=(MAX_SAFE_INTEGER as f64);
-            let whole = f64_representation.fract() == 0.0;
+    /// Sets a function that is given each value before its type is inspected, and can return a
+    /// [`HookAction`] to deserialize it differently.
+    ///
+    /// This allows recognizing specific object shapes, like instances of a custom class, that
+    /// would otherwise be deserialized as maps. Returning `None` keeps the generic behavior. The
+    /// hook is consulted for self-describing deserialization and types that rely on it, like
+    /// numbers, strings, maps and structs, but not for booleans, sequences or options. A value
+    /// returned with [`HookAction::Value`] isn't passed to the hook again.
+    pub fn hook<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: Fn(&JSValueRef<'de>) -> Option<HookAction<'de>> + 'de,
+    {
+        self.hook = Some(Box::new(hook));
+        self
+    }
+
+    /// Consults the hook for the current value. Object keys aren't passed to the hook.
+    fn hook_action(&self) -> Option<HookAction<'de>> {
+        self.hook.as_ref().and_then(|hook| hook(&self.value))
+    }
+}
+
+impl Deserializer<'_> {
+    /// Coerces any value to `bool` with JavaScript's truthiness rules when deserializing a
+    /// `bool`.
+    ///
+    /// Disabled by default, in which case only `true` and `false` are accepted. Note that
+    /// JavaScript considers every non-empty string truthy, so `"false"` becomes `true`.
+    pub fn truthy_bools(&mut self, enabled: bool) -> &mut Self {
+        self.truthy_bools = enabled;
+        self
+    }
+
+    /// Accepts array-like objects, whose keys are the integers `0..n`, when deserializing a
+    /// sequence.
+    ///
+    /// An optional `length` property is allowed as long as it matches the number of elements.
+    /// Objects with gaps between their integer keys, or with other keys, are still treated as
+    /// maps. Disabled by default.
+    pub fn array_like_objects(&mut self, enabled: bool) -> &mut Self {
+        self.array_like_objects = enabled;
+        self
+    }
+
+    /// Checks that the value being deserialized matches `schema`, see [`Schema`].
+    ///
+    /// The whole value is checked before anything is deserialized, so the error lists every
+    /// violation instead of only the first one.
+    pub fn validate(&mut self, schema: &Schema) -> Result<&mut Self> {
+        schema.validate(&self.value)?;
+        Ok(self)
+    }
+
+    /// Skips `null` and `undefined` elements of arrays, so `[1, null, 3]` deserializes into a
+    /// `Vec<i32>` as `[1, 3]`.
+    ///
+    /// Holes in sparse arrays read as `undefined` and are skipped as well. This also applies to
+    /// tuples, which then need enough non-null elements. To replace `null` elements with the
+    /// element type's default value instead, see [`crate::adapters::null_as_default`]. Disabled by
+    /// default.
+    pub fn skip_null_elements(&mut self, enabled: bool) -> &mut Self {
+        self.skip_null_elements = enabled;
+        self
+    }
+
+    /// Skips object properties whose value is `undefined`, so `{a: undefined, b: 1}` deserializes
+    /// into a `BTreeMap<String, i32>` as `{"b": 1}`.
+    ///
+    /// This matches `JSON.stringify`, which leaves these properties out. When disabled, the
+    /// `undefined` values are deserialized like any other value, which fails for types that
+    /// don't accept a unit. Disabled by default.
+    pub fn skip_undefined_properties(&mut self, enabled: bool) -> &mut Self {
+        self.skip_undefined_properties = enabled;
+        self
+    }
 
-            if whole && is_positive && f64_representation <= u32::MAX as f64 {
-                return visitor.visit_u32(f64_representation as u32);
+    /// Trims leading and trailing whitespace from strings when deserializing a `str` or `String`.
+    ///
+    /// Whitespace is what `str::trim` removes. Types that deserialize through other methods,
+    /// like `char`, and object keys are left untouched. Disabled by default.
+    pub fn trim_strings(&mut self, enabled: bool) -> &mut Self {
+        self.trim_strings = enabled;
+        self
+    }
+
+    /// Converts string values to lower or upper case when deserializing them, or leaves them as
+    /// they are with `None`, the default.
+    ///
+    /// Object keys are never converted. Strings naming the variant of an enum are only converted
+    /// when [`Deserializer::normalize_enum_tags`] is enabled, except for internally tagged and
+    /// untagged enums, whose tags serde reads like any other value.
+    pub fn normalize_string_values(&mut self, case: Option<Case>) -> &mut Self {
+        self.normalize_string_values = case;
+        self
+    }
+
+    /// Applies [`Deserializer::normalize_string_values`] to the variant names of enums as well.
+    ///
+    /// Disabled by default.
+    pub fn normalize_enum_tags(&mut self, enabled: bool) -> &mut Self {
+        self.normalize_enum_tags = enabled;
+        self
+    }
+
+    /// Limits the number of elements read from an iterable when deserializing a sequence.
+    ///
+    /// Objects implementing `Symbol.iterator`, like generators, are deserialized by calling
+    /// `next()` until the iterator is done. Since nothing guarantees that it ever is, iterating
+    /// fails once more than `max` elements have been produced. The limit also applies to the size
+    /// of `Set`s and `Map`s. Defaults to 2^20 (1,048,576) elements, so infinite generators fail
+    /// quickly.
+    pub fn max_iterator_len(&mut self, max: usize) -> &mut Self {
+        self.max_iterator_len = max;
+        self
+    }
+
+    /// Limits the total number of values deserialized.
+    ///
+    /// Every array element and property value counts against the limit, however deeply it's
+    /// nested, and deserializing fails once more than `max` values have been visited. This bounds
+    /// the work done for inputs that are wide rather than deep, like an array with millions of
+    /// elements. Unlimited by default.
+    pub fn max_nodes(&mut self, max: usize) -> &mut Self {
+        self.max_nodes = max;
+        self
+    }
+
+    /// Interns object keys, converting each distinct key from a JavaScript string only once.
+    ///
+    /// Repeated keys are then read from a cache owned by the deserializer instead of being
+    /// converted again for every object, which reduces allocations when deserializing large
+    /// datasets where many objects share the same keys. The cache lives as long as the
+    /// deserializer. Disabled by default.
+    pub fn intern_keys(&mut self, enabled: bool) -> &mut Self {
+        self.key_cache = enabled.then(HashMap::new);
+        self
+    }
+
+    /// Records the keys of the top-level object as they're deserialized, so they can be read with
+    /// [`Deserializer::seen_keys`] afterwards.
+    ///
+    /// This tells a property that was left out apart from one set to its default, which PATCH-style
+    /// APIs need to only update the fields that were provided. Keys skipped by
+    /// [`Deserializer::allowed_keys`] or [`Deserializer::skip_undefined_properties`] aren't
+    /// recorded. Disabled by default.
+    pub fn record_seen_keys(&mut self, enabled: bool) -> &mut Self {
+        self.seen_keys = enabled.then(HashSet::new);
+        self
+    }
+
+    /// Returns the keys of the top-level object deserialized so far, or `None` if
+    /// [`Deserializer::record_seen_keys`] isn't enabled.
+    pub fn seen_keys(&self) -> Option<&HashSet<String>> {
+        self.seen_keys.as_ref()
+    }
+
+    /// Records `key` for [`Deserializer::seen_keys`] if it belongs to the top-level object.
+    fn record_key(&mut self, key: &str) {
+        Self::record_key_at(&mut self.seen_keys, self.ancestors.len(), key);
+    }
+
+    /// Like [`Deserializer::record_key`] but only borrows the seen keys, so `key` can be borrowed
+    /// from another field like the key cache.
+    fn record_key_at(seen_keys: &mut Option<HashSet<String>>, depth: usize, key: &str) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(key, depth, "deserializing field");
+        if let Some(seen_keys) = seen_keys {
+            if depth == 1 {
+                seen_keys.insert(key.to_string());
             }
+        }
+    }
+
+    /// Only accepts object keys from `keys`, handling any other key as selected by `disallowed`.
+    ///
+    /// The allowlist applies to the keys of every object, nested ones included. Since keys like
+    /// `__proto__` can only get through when they're listed, this guards against
+    /// prototype-pollution-style input when the deserialized data is later merged into other
+    /// objects.
+    pub fn allowed_keys<I, K>(&mut self, keys: I, disallowed: DisallowedKeys) -> &mut Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        let keys = keys.into_iter().map(Into::into).collect();
+        self.allowed_keys = Some(KeyAllowlist { keys, disallowed });
+        self
+    }
 
-            if whole && safe_integer_range.contains(&f64_representation) {
-                return visitor.visit_i64(f64_representation as i64);
+    /// Checks `key` against the allowlist, returning `false` if it should be skipped.
+    fn is_key_allowed(&self, key: &str) -> Result<bool> {
+        match &self.allowed_keys {
+            Some(allowlist) => allowlist.allows(key),
+            None => Ok(true),
+        }
+    }
+
+    /// Runs `f` with the current object or array marked as being deserialized, failing if it
+    /// already is, which means that it references itself.
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let id: JSValueRaw = self.value.into();
+        if self.ancestors.contains(&id) {
+            return Err(Error::Custom(anyhow!("circular reference")));
+        }
+        self.ancestors.push(id);
+        #[cfg(feature = "tracing")]
+        let span = tracing::trace_span!(
+            "nested",
+            kind = type_name(&self.value),
+            depth = self.ancestors.len()
+        )
+        .entered();
+        let result = f(self);
+        #[cfg(feature = "tracing")]
+        {
+            if let Err(err) = &result {
+                tracing::debug!(error = %err, "deserialization failed");
             }
+            drop(span);
+        }
+        self.ancestors.pop();
+        result
+    }
+
+    fn deserialize_number<'de, V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.value.is_repr_as_i32() {
+            return visitor.visit_i32(self.value.as_i32_unchecked());
+        }
 
-            return visitor.visit_f64(f64_representation);
+        if self.value.is_repr_as_f64() {
+            return visit_f64(self.value.as_f64_unchecked(), visitor);
         }
         unreachable!()
     }
@@ -80,6 +487,14 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: de::Visitor<'de>,
     {
+        if let Some(action) = self.hook_action() {
+            match action {
+                HookAction::Value(value) => self.value = value,
+                HookAction::String(val) => return visitor.visit_string(val),
+                HookAction::Number(val) => return visit_f64(val, visitor),
+            }
+        }
+
         if self.value.is_number() {
             return self.deserialize_number(visitor);
         }
@@ -102,39 +517,61 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         }
 
         if self.value.is_str() {
-            if self.map_key {
-                self.map_key = false;
-                let key = as_key(&self.value)?;
-                return visitor.visit_str(key);
-            } else {
-                let val = self.value.as_str()?;
-                return visitor.visit_str(val);
-            }
+            let val = self.value.as_str()?;
+            return self.visit_string_value(val, visitor);
         }
 
         if self.value.is_array() {
             let val = self.value.get_property("length")?;
             let length = val.as_u32_unchecked();
-            let seq = self.value;
-            let seq_access = SeqAccess {
-                de: self,
-                length,
-                seq,
-                index: 0,
-            };
-            return visitor.visit_seq(seq_access);
+            return self.nested(|de| {
+                let seq = de.value;
+                let seq_access = SeqAccess {
+                    de,
+                    length,
+                    seq,
+                    index: 0,
+                };
+                visitor.visit_seq(seq_access)
+            });
         }
 
         if self.value.is_object() {
             if self.value.is_array_buffer() {
                 return visitor.visit_bytes(self.value.as_bytes()?);
+            } else if self.value.is_error() {
+                return self.nested(|de| {
+                    let error = de.value;
+                    let properties = error.properties()?;
+                    let error_access = ErrorAccess {
+                        de,
+                        error,
+                        error_keys: ERROR_KEYS.iter(),
+                        properties,
+                        value: None,
+                    };
+                    visitor.visit_map(error_access)
+                });
             } else {
-                let properties = self.value.properties()?;
-                let map_access = MapAccess {
-                    de: self,
-                    properties,
-                };
-                return visitor.visit_map(map_access);
+                return self.nested(|de| {
+                    let properties = de.value.properties()?;
+                    if properties.is_empty() {
+                        if let Some(entries_access) = MapEntriesAccess::new(de)? {
+                            return visitor.visit_map(entries_access);
+                        }
+                        for name in WEAK_COLLECTIONS {
+                            if de.value.is_instance_of_global(name)? {
+                                return Err(Error::Custom(anyhow!("cannot deserialize {name}")));
+                            }
+                        }
+                    }
+                    let map_access = MapAccess {
+                        de,
+                        properties,
+                        value: None,
+                    };
+                    visitor.visit_map(map_access)
+                });
             }
         }
 
@@ -145,7 +582,125 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     }
 
     fn is_human_readable(&self) -> bool {
-        false
+        true
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.value.is_bool() {
+            visitor.visit_bool(self.value.as_bool()?)
+        } else if self.truthy_bools {
+            visitor.visit_bool(self.value.coerce_to_bool()?)
+        } else {
+            Err(Error::TypeMismatch(format!(
+                "expected a boolean, found {}",
+                type_name(&self.value)
+            )))
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if let Some(action) = self.hook_action() {
+            match action {
+                HookAction::Value(value) => self.value = value,
+                HookAction::String(val) => return visitor.visit_string(val),
+                HookAction::Number(val) => return visit_f64(val, visitor),
+            }
+        }
+
+        if self.value.is_str() {
+            let val = self.value.as_str()?;
+            self.visit_string_value(if self.trim_strings { val.trim() } else { val }, visitor)
+        } else {
+            Err(Error::TypeMismatch(format!(
+                "expected a string, found {}",
+                type_name(&self.value)
+            )))
+        }
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.array_like_objects && self.value.is_object() {
+            if let Some(length) = array_like_length(&self.value)? {
+                return self.nested(|de| {
+                    let seq = de.value;
+                    let seq_access = SeqAccess {
+                        de,
+                        length,
+                        seq,
+                        index: 0,
+                    };
+                    visitor.visit_seq(seq_access)
+                });
+            }
+        }
+
+        if self.value.is_object() && !self.value.is_array() {
+            if let Some(iterator) = self.value.iterator()? {
+                // A `Set`'s size is known up front, so it's checked before iterating and used as a
+                // size hint.
+                let len = if self.value.is_instance_of_global("Set")? {
+                    let len = self.value.get_property("size")?.as_u32_unchecked() as usize;
+                    if len > self.max_iterator_len {
+                        return Err(Error::Custom(anyhow!(
+                            "Set has {len} elements, more than the maximum of {}",
+                            self.max_iterator_len
+                        )));
+                    }
+                    Some(len)
+                } else {
+                    None
+                };
+                return self.nested(|de| {
+                    let next = iterator.get_property("next")?;
+                    let iter_access = IteratorAccess {
+                        de,
+                        iterator,
+                        next,
+                        count: 0,
+                        len,
+                    };
+                    visitor.visit_seq(iter_access)
+                });
+            }
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Fixed-size byte arrays like `[u8; 32]` are read straight from the bytes of `Uint8Array`s
+        // and `ArrayBuffer`s, which have to be exactly as long. Arrays of numbers and other
+        // iterables are deserialized like sequences.
+        if self.value.is_array_buffer()
+            || (self.value.is_object() && self.value.is_instance_of_global("Uint8Array")?)
+        {
+            let bytes = self.value.as_bytes()?;
+            if bytes.len() != len {
+                return Err(Error::invalid_length(bytes.len(), &visitor));
+            }
+            return visitor.visit_seq(de::value::SeqDeserializer::<_, Error>::new(
+                bytes.iter().copied(),
+            ));
+        }
+        self.deserialize_seq(visitor)
     }
 
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
@@ -159,10 +714,26 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         }
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
+        if name == raw_number::TOKEN {
+            if !self.value.is_number() && !self.value.is_big_int() {
+                return Err(Error::TypeMismatch(format!(
+                    "expected a number, found {}",
+                    type_name(&self.value)
+                )));
+            }
+            let text = self.value.coerce_to_string()?;
+            return visitor.visit_newtype_struct(de::value::StringDeserializer::<Error>::new(text));
+        }
+        if name == negative_zero::TOKEN && self.value.is_negative_zero() {
+            return visitor.visit_f64(-0.0);
+        }
+        if name == raw_value::TOKEN {
+            return raw_value::with_current(&self.value, || visitor.visit_unit());
+        }
         visitor.visit_newtype_struct(self)
     }
 
@@ -170,24 +741,154 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         self,
         _name: &'static str,
         _variants: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        unimplemented!()
+        // Unit variants are strings, or indices with `Serializer::enum_as_index`, and other
+        // variants are objects with the variant's name as their only key, as written by
+        // `Serializer`.
+        let tag_case = self
+            .normalize_string_values
+            .filter(|_| self.normalize_enum_tags);
+        if self.value.is_str() {
+            let variant = self.value.as_str()?;
+            return match tag_case {
+                Some(case) => {
+                    visitor.visit_enum(de::value::StringDeserializer::new(case.apply(variant)))
+                }
+                None => visitor.visit_enum(de::value::StrDeserializer::new(variant)),
+            };
+        }
+        if self.value.is_repr_as_i32() && self.value.as_i32_unchecked() >= 0 {
+            let index = self.value.as_i32_unchecked() as u32;
+            return visitor.visit_enum(de::value::U32Deserializer::<Error>::new(index));
+        }
+
+        if self.value.is_object() && !self.value.is_array() {
+            let mut properties = self.value.properties()?;
+            if let Some(key) = properties.next_key()? {
+                let variant = as_key(&key)?;
+                let variant = match tag_case {
+                    Some(case) => case.apply(variant),
+                    None => variant.to_string(),
+                };
+                let value = properties.next_value()?;
+                if properties.next_key()?.is_none() {
+                    return visitor.visit_enum(EnumAccess {
+                        de: self,
+                        variant,
+                        value,
+                    });
+                }
+            }
+        }
+
+        Err(Error::TypeMismatch(format!(
+            "expected a string, an index or an object with a single key, found {}",
+            type_name(&self.value)
+        )))
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Skip the value without reading its contents, unless disallowed keys nested in it have
+        // to be reported.
+        match &self.allowed_keys {
+            Some(allowlist) if allowlist.disallowed == DisallowedKeys::Error => {
+                self.deserialize_any(visitor)
+            }
+            _ => visitor.visit_unit(),
+        }
     }
 
     forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf unit unit_struct seq tuple
-        tuple_struct map struct identifier ignored_any
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        bytes byte_buf unit unit_struct
+        tuple_struct map struct identifier
+    }
+}
+
+/// Returns the number of elements of an array-like object, or `None` if `value` has keys other
+/// than `0..n` and a matching `length`.
+fn array_like_length(value: &JSValueRef) -> Result<Option<u32>> {
+    let mut properties = value.properties()?;
+    let mut length = 0;
+    let mut declared_length = None;
+    while let Some(key) = properties.next_key()? {
+        if !key.is_str() {
+            return Ok(None);
+        }
+        let key = key.as_str()?;
+        if key == "length" {
+            declared_length = Some(properties.next_value()?);
+        } else if key == length.to_string() {
+            length += 1;
+        } else {
+            return Ok(None);
+        }
+    }
+
+    match declared_length {
+        Some(declared) if declared.try_as_integer().ok() != Some(length as i32) => Ok(None),
+        _ => Ok(Some(length)),
+    }
+}
+
+/// Visits a number stored as `f64`, using an integer when it has no fractional part so it can be
+/// deserialized into integer types.
+fn visit_f64<'de, V>(f64_representation: f64, visitor: V) -> Result<V::Value>
+where
+    V: de::Visitor<'de>,
+{
+    let is_positive = f64_representation.is_sign_positive();
+    let safe_integer_range = (MIN_SAFE_INTEGER as f64)..=(MAX_SAFE_INTEGER as f64);
+    let whole = f64_representation.fract() == 0.0;
+
+    if whole && is_positive && f64_representation <= u32::MAX as f64 {
+        return visitor.visit_u32(f64_representation as u32);
+    }
+
+    if whole && safe_integer_range.contains(&f64_representation) {
+        return visitor.visit_i64(f64_representation as i64);
+    }
+
+    visitor.visit_f64(f64_representation)
+}
+
+/// Describes the type of `value` for error messages.
+pub(super) fn type_name(value: &JSValueRef) -> &'static str {
+    if value.is_number() {
+        "number"
+    } else if value.is_big_int() {
+        "bigint"
+    } else if value.is_bool() {
+        "boolean"
+    } else if value.is_null() {
+        "null"
+    } else if value.is_undefined() {
+        "undefined"
+    } else if value.is_str() {
+        "string"
+    } else if value.is_array() {
+        "array"
+    } else if value.is_function() {
+        "function"
+    } else if value.is_object() {
+        "object"
+    } else {
+        "unknown value"
     }
 }
 
 struct MapAccess<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
     properties: Properties<'de>,
+    /// The value of the current key if it was already read to check whether it's `undefined`.
+    value: Option<JSValueRef<'de>>,
 }
 
 impl<'a, 'de> de::MapAccess<'de> for MapAccess<'a, 'de> {
@@ -197,90 +898,513 @@ impl<'a, 'de> de::MapAccess<'de> for MapAccess<'a, 'de> {
     where
         K: de::DeserializeSeed<'de>,
     {
-        if let Some(key) = self.properties.next_key()? {
-            self.de.value = key;
-            self.de.map_key = true;
-            seed.deserialize(&mut *self.de).map(Some)
-        } else {
-            Ok(None)
+        if self.de.key_cache.is_some() {
+            return self.next_cached_key_seed(seed);
+        }
+
+        while let Some(key) = self.properties.next_key()? {
+            let key = as_key(&key)?;
+            if !self.de.is_key_allowed(key)? {
+                continue;
+            }
+            if self.de.skip_undefined_properties {
+                let value = self.properties.next_value()?;
+                if value.is_undefined() {
+                    continue;
+                }
+                self.value = Some(value);
+            }
+            self.de.record_key(key);
+            return seed.deserialize(MapKeyDeserializer { key }).map(Some);
         }
+        Ok(None)
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
     where
         V: de::DeserializeSeed<'de>,
     {
-        self.de.value = self.properties.next_value()?;
+        let value = match self.value.take() {
+            Some(value) => value,
+            None => self.properties.next_value()?,
+        };
+        self.de.enter(value)?;
         seed.deserialize(&mut *self.de)
     }
 }
 
-struct SeqAccess<'a, 'de: 'a> {
+/// Reads the entries of a JavaScript `Map` through its iterator, with keys deserialized like any
+/// other value.
+struct MapEntriesAccess<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
-    seq: JSValueRef<'de>,
-    length: u32,
-    index: u32,
+    iterator: JSValueRef<'de>,
+    next: JSValueRef<'de>,
+    len: usize,
+    count: usize,
+    /// The value of the entry whose key was last read.
+    value: Option<JSValueRef<'de>>,
 }
 
-impl<'a, 'de> de::SeqAccess<'de> for SeqAccess<'a, 'de> {
+impl<'a, 'de> MapEntriesAccess<'a, 'de> {
+    /// Returns an accessor for the entries of the current value if it's a `Map`.
+    fn new(de: &'a mut Deserializer<'de>) -> Result<Option<Self>> {
+        if !de.value.is_instance_of_global("Map")? {
+            return Ok(None);
+        }
+        let len = de.value.get_property("size")?.as_u32_unchecked() as usize;
+        if len > de.max_iterator_len {
+            return Err(Error::Custom(anyhow!(
+                "Map has {len} entries, more than the maximum of {}",
+                de.max_iterator_len
+            )));
+        }
+        let Some(iterator) = de.value.iterator()? else {
+            return Ok(None);
+        };
+        let next = iterator.get_property("next")?;
+        Ok(Some(Self {
+            de,
+            iterator,
+            next,
+            len,
+            count: 0,
+            value: None,
+        }))
+    }
+}
+
+impl<'a, 'de> de::MapAccess<'de> for MapEntriesAccess<'a, 'de> {
     type Error = Error;
 
-    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
     where
-        T: de::DeserializeSeed<'de>,
+        K: de::DeserializeSeed<'de>,
     {
-        if self.index < self.length {
-            self.de.value = self.seq.get_indexed_property(self.index)?;
-            self.index += 1;
-            seed.deserialize(&mut *self.de).map(Some)
-        } else {
-            Ok(None)
+        let result = self.next.call(&self.iterator, &[])?;
+        if !result.is_object() {
+            return Err(Error::TypeMismatch(format!(
+                "expected an iterator result object, found {}",
+                type_name(&result)
+            )));
         }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use std::collections::BTreeMap;
+        if result.get_property("done")?.coerce_to_bool()? {
+            return Ok(None);
+        }
+        if self.count == self.de.max_iterator_len {
+            return Err(Error::Custom(anyhow!(
+                "Map has more than {} entries",
+                self.de.max_iterator_len
+            )));
+        }
+        self.count += 1;
 
-    use super::Deserializer as ValueDeserializer;
-    use crate::js_binding::constants::MAX_SAFE_INTEGER;
-    use crate::js_binding::context::JSContextRef;
-    use crate::js_binding::value::JSValueRef;
-    use serde::de::DeserializeOwned;
-    use serde_bytes::ByteBuf;
+        let entry = result.get_property("value")?;
+        self.value = Some(entry.get_indexed_property(1)?);
+        let key = entry.get_indexed_property(0)?;
+        // String keys are read like object keys, so options transforming string values and
+        // `max_nodes` don't apply to them. Other keys are deserialized like any other value.
+        if key.is_str() {
+            let key = key.as_str_lossy();
+            self.de.record_key(&key);
+            return seed.deserialize(MapKeyDeserializer { key: &key }).map(Some);
+        }
+        self.de.enter(key)?;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
 
-    fn deserialize_value<T>(v: JSValueRef) -> T
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
     where
-        T: DeserializeOwned,
+        V: de::DeserializeSeed<'de>,
     {
-        let mut deserializer = ValueDeserializer::from(v);
-        T::deserialize(&mut deserializer).unwrap()
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error::Custom(anyhow!("Map value requested before its key")))?;
+        self.de.enter(value)?;
+        seed.deserialize(&mut *self.de)
     }
 
-    #[test]
-    fn test_null() {
-        let context = JSContextRef::default();
-        let val = context.null_value().unwrap();
-        deserialize_value::<()>(val);
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len.saturating_sub(self.count))
     }
+}
 
-    #[test]
-    fn test_undefined() {
-        let context = JSContextRef::default();
-        let val = context.undefined_value().unwrap();
-        deserialize_value::<()>(val);
-    }
+/// Built-ins whose entries aren't exposed as properties. Instances would deserialize as empty maps,
+/// losing their contents without notice, so they are rejected instead.
+const WEAK_COLLECTIONS: [&str; 3] = ["WeakMap", "WeakSet", "WeakRef"];
 
-    #[test]
-    fn test_nan() {
-        let context = JSContextRef::default();
-        let val = context.value_from_f64(f64::NAN).unwrap();
-        let actual = deserialize_value::<f64>(val);
-        assert!(actual.is_nan());
-    }
+/// Keys read from `Error` objects on top of their enumerable properties. `name` is inherited from
+/// the prototype and `message` and `stack` aren't enumerable, so they'd be missed otherwise.
+const ERROR_KEYS: [&str; 3] = ["name", "message", "stack"];
 
-    #[test]
+struct ErrorAccess<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    error: JSValueRef<'de>,
+    error_keys: std::slice::Iter<'static, &'static str>,
+    properties: Properties<'de>,
+    value: Option<JSValueRef<'de>>,
+}
+
+impl<'a, 'de> de::MapAccess<'de> for ErrorAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        for key in self.error_keys.by_ref() {
+            let value = self.error.get_property(*key)?;
+            if !value.is_undefined() && self.de.is_key_allowed(key)? {
+                self.value = Some(value);
+                self.de.record_key(key);
+                return seed.deserialize(MapKeyDeserializer { key }).map(Some);
+            }
+        }
+
+        while let Some(key) = self.properties.next_key()? {
+            // Properties like `name` assigned on the error itself have already been visited.
+            let name = as_key(&key)?;
+            if ERROR_KEYS.contains(&name) || !self.de.is_key_allowed(name)? {
+                continue;
+            }
+            if self.de.skip_undefined_properties {
+                let value = self.properties.next_value()?;
+                if value.is_undefined() {
+                    continue;
+                }
+                self.value = Some(value);
+            }
+            self.de.record_key(name);
+            return seed.deserialize(MapKeyDeserializer { key: name }).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let value = match self.value.take() {
+            Some(value) => value,
+            None => self.properties.next_value()?,
+        };
+        self.de.enter(value)?;
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+impl<'de> MapAccess<'_, 'de> {
+    /// Like `next_key_seed` but looks up the key's string in the deserializer's key cache.
+    fn next_cached_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        while let Some(atom) = self.properties.next_atom() {
+            let de = &mut *self.de;
+            let cache = de.key_cache.get_or_insert_with(HashMap::new);
+            let key = match cache.entry(atom) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let key = self.properties.atom_to_string(atom)?;
+                    entry.insert(as_key(&key)?.to_string())
+                }
+            }
+            .as_str();
+            if let Some(allowlist) = &de.allowed_keys {
+                if !allowlist.allows(key)? {
+                    continue;
+                }
+            }
+            if de.skip_undefined_properties {
+                let value = self.properties.next_value()?;
+                if value.is_undefined() {
+                    continue;
+                }
+                self.value = Some(value);
+            }
+            Deserializer::record_key_at(&mut de.seen_keys, de.ancestors.len(), key);
+            return seed.deserialize(MapKeyDeserializer { key }).map(Some);
+        }
+        Ok(None)
+    }
+}
+
+/// Reads the variant of an enum from an object with a single key.
+struct EnumAccess<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    variant: String,
+    value: JSValueRef<'de>,
+}
+
+impl<'a, 'de> de::EnumAccess<'de> for EnumAccess<'a, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(de::value::StrDeserializer::<Error>::new(&self.variant))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'a, 'de> de::VariantAccess<'de> for EnumAccess<'a, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        if self.value.is_null_or_undefined() {
+            Ok(())
+        } else {
+            Err(Error::TypeMismatch(format!(
+                "expected null for unit variant `{}`, found {}",
+                self.variant,
+                type_name(&self.value)
+            )))
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.de.enter(self.value)?;
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.de.enter(self.value)?;
+        de::Deserializer::deserialize_seq(&mut *self.de, visitor)
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.de.enter(self.value)?;
+        de::Deserializer::deserialize_any(&mut *self.de, visitor)
+    }
+}
+
+/// Deserializes an object key. JavaScript object keys are always strings, so numeric keys are
+/// parsed from the string when the target expects a number, e.g. for `HashMap<u64, V>`.
+struct MapKeyDeserializer<'a> {
+    key: &'a str,
+}
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: de::Visitor<'de>,
+            {
+                let val = self.key.parse::<$ty>().map_err(|_| {
+                    Error::TypeMismatch(format!(
+                        "expected a key that parses as {}, found `{}`",
+                        stringify!($ty),
+                        self.key
+                    ))
+                })?;
+                visitor.$visit(val)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for MapKeyDeserializer<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_str(self.key)
+    }
+
+    deserialize_parsed_key! {
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_i128 => visit_i128(i128),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_u128 => visit_u128(u128),
+        deserialize_f32 => visit_f32(f32),
+        deserialize_f64 => visit_f64(f64),
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_enum(de::value::StrDeserializer::new(self.key))
+    }
+
+    forward_to_deserialize_any! {
+        bool char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct SeqAccess<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    seq: JSValueRef<'de>,
+    length: u32,
+    index: u32,
+}
+
+impl<'a, 'de> de::SeqAccess<'de> for SeqAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        while self.index < self.length {
+            let element = self.seq.get_indexed_property(self.index)?;
+            self.index += 1;
+            if self.de.skip_null_elements && element.is_null_or_undefined() {
+                continue;
+            }
+            self.de.enter(element)?;
+            return seed.deserialize(&mut *self.de).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        // The number of skipped elements isn't known until they're read.
+        if self.de.skip_null_elements {
+            None
+        } else {
+            Some((self.length - self.index) as usize)
+        }
+    }
+}
+
+/// Reads the elements of a sequence from a JavaScript iterator.
+struct IteratorAccess<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    iterator: JSValueRef<'de>,
+    next: JSValueRef<'de>,
+    count: usize,
+    /// The number of elements, when it's known before iterating.
+    len: Option<usize>,
+}
+
+impl<'a, 'de> de::SeqAccess<'de> for IteratorAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        loop {
+            let result = self.next.call(&self.iterator, &[])?;
+            if !result.is_object() {
+                return Err(Error::TypeMismatch(format!(
+                    "expected an iterator result object, found {}",
+                    type_name(&result)
+                )));
+            }
+            if result.get_property("done")?.coerce_to_bool()? {
+                return Ok(None);
+            }
+
+            if self.count == self.de.max_iterator_len {
+                return Err(Error::Custom(anyhow!(
+                    "iterator produced more than {} elements",
+                    self.de.max_iterator_len
+                )));
+            }
+            self.count += 1;
+
+            let element = result.get_property("value")?;
+            if self.de.skip_null_elements && element.is_null_or_undefined() {
+                continue;
+            }
+            self.de.enter(element)?;
+            return seed.deserialize(&mut *self.de).map(Some);
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.len.map(|len| len.saturating_sub(self.count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    use super::{Case, Deserializer as ValueDeserializer, DisallowedKeys, HookAction};
+    use crate::js_binding::constants::MAX_SAFE_INTEGER;
+    use crate::js_binding::context::JSContextRef;
+    use crate::js_binding::value::JSValueRef;
+    use serde::de::{DeserializeOwned, IgnoredAny};
+    use serde::Deserialize;
+    use serde_bytes::ByteBuf;
+    use smallvec::SmallVec;
+
+    fn deserialize_value<T>(v: JSValueRef) -> T
+    where
+        T: DeserializeOwned,
+    {
+        let mut deserializer = ValueDeserializer::from(v);
+        T::deserialize(&mut deserializer).unwrap()
+    }
+
+    #[test]
+    fn test_null() {
+        let context = JSContextRef::default();
+        let val = context.null_value().unwrap();
+        deserialize_value::<()>(val);
+    }
+
+    #[test]
+    fn test_undefined() {
+        let context = JSContextRef::default();
+        let val = context.undefined_value().unwrap();
+        deserialize_value::<()>(val);
+    }
+
+    #[test]
+    fn test_nan() {
+        let context = JSContextRef::default();
+        let val = context.value_from_f64(f64::NAN).unwrap();
+        let actual = deserialize_value::<f64>(val);
+        assert!(actual.is_nan());
+    }
+
+    #[test]
     fn test_infinity() {
         let context = JSContextRef::default();
         let val = context.value_from_f64(f64::INFINITY).unwrap();
@@ -310,15 +1434,36 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
-    fn test_map_does_not_support_non_string_keys() {
-        // Sanity check to make sure it's not possible to deserialize
-        // to a map where keys are not strings (e.g. numerical value).
+    fn test_map_with_numeric_keys() {
         let context = JSContextRef::default();
-        context.eval_global("main", "var a = {1337: 42};").unwrap();
+        context
+            .eval_global("main", "var a = {1337: 42, '-1': 7};")
+            .unwrap();
         let val = context.global_object().unwrap().get_property("a").unwrap();
 
-        deserialize_value::<BTreeMap<i32, i32>>(val);
+        let actual = deserialize_value::<BTreeMap<i32, i32>>(val);
+        assert_eq!(BTreeMap::from([(-1, 7), (1337, 42)]), actual);
+
+        let val = context
+            .eval_global("main", "({0: 'a', '18446744073709551615': 'b'})")
+            .unwrap();
+        let actual = deserialize_value::<HashMap<u64, String>>(val);
+        assert_eq!(
+            HashMap::from([(0, "a".to_string()), (u64::MAX, "b".to_string())]),
+            actual
+        );
+    }
+
+    #[test]
+    fn test_map_with_unparseable_numeric_key() {
+        let context = JSContextRef::default();
+        let val = context.eval_global("main", "({1: 'a', foo: 'b'})").unwrap();
+        let err =
+            HashMap::<u64, String>::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert_eq!(
+            "expected a key that parses as u64, found `foo`",
+            err.to_string()
+        );
     }
 
     #[test]
@@ -407,6 +1552,38 @@ mod tests {
         assert_eq!(vec![42u8, 0, 255], val);
     }
 
+    #[test]
+    fn test_fixed_size_byte_arrays() {
+        let context = JSContextRef::default();
+        let deserialize = |source: &str| {
+            let val = context.eval_global("main", source).unwrap();
+            <[u8; 32]>::deserialize(&mut ValueDeserializer::from(val))
+        };
+        let expected: [u8; 32] = std::array::from_fn(|i| i as u8);
+        for source in [
+            "Uint8Array.from({length: 32}, (_, i) => i)",
+            "Uint8Array.from({length: 32}, (_, i) => i).buffer",
+            "Uint8Array.from({length: 40}, (_, i) => i - 4).subarray(4, 36)",
+            "Array.from({length: 32}, (_, i) => i)",
+        ] {
+            assert_eq!(expected, deserialize(source).unwrap(), "{source}");
+        }
+
+        let err = deserialize("new Uint8Array(31)").unwrap_err();
+        assert_eq!(
+            "invalid length 31, expected an array of length 32",
+            err.to_string()
+        );
+        assert!(deserialize("new ArrayBuffer(33)").is_err());
+        assert!(deserialize("[1, 2, 3]").is_err());
+
+        let val = context
+            .eval_global("main", "new Uint8Array([1, 2])")
+            .unwrap();
+        let pair = <(u8, u8)>::deserialize(&mut ValueDeserializer::from(val)).unwrap();
+        assert_eq!((1, 2), pair);
+    }
+
     #[test]
     fn test_array() {
         let context = JSContextRef::default();
@@ -419,4 +1596,1185 @@ mod tests {
 
         assert_eq!(vec![1, 2, 3], val);
     }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Inner {
+        name: String,
+        count: u32,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Shared(Arc<Inner>);
+
+    #[test]
+    fn test_smart_pointers() {
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global("main", "({name: 'javy', count: 3})")
+            .unwrap();
+        let expected = Inner {
+            name: "javy".to_string(),
+            count: 3,
+        };
+
+        assert_eq!(Box::new(expected), deserialize_value::<Box<Inner>>(val));
+        assert_eq!(3, deserialize_value::<Rc<Inner>>(val).count);
+        // The newtype is handed the object itself rather than a wrapped copy of it.
+        assert_eq!("javy", deserialize_value::<Shared>(val).0.name);
+
+        let val = context.value_from_str("shared").unwrap();
+        assert_eq!("shared", deserialize_value::<Arc<String>>(val).as_str());
+        assert_eq!("shared", &*deserialize_value::<Arc<str>>(val));
+    }
+
+    #[test]
+    fn test_bool_is_strict_by_default() {
+        let context = JSContextRef::default();
+        assert!(deserialize_value::<bool>(
+            context.value_from_bool(true).unwrap()
+        ));
+
+        let val = context.value_from_i32(1).unwrap();
+        let err = bool::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert_eq!("expected a boolean, found number", err.to_string());
+
+        let val = context.value_from_str("true").unwrap();
+        let err = bool::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert_eq!("expected a boolean, found string", err.to_string());
+    }
+
+    #[test]
+    fn test_truthy_bools() {
+        let context = JSContextRef::default();
+        for (source, expected) in [
+            ("false", false),
+            ("0", false),
+            ("1", true),
+            ("''", false),
+            ("'true'", true),
+            ("'false'", true),
+            ("null", false),
+        ] {
+            let val = context.eval_global("main", source).unwrap();
+            let mut deserializer = ValueDeserializer::from(val);
+            deserializer.truthy_bools(true);
+            assert_eq!(
+                expected,
+                bool::deserialize(&mut deserializer).unwrap(),
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_array_like_objects() {
+        let context = JSContextRef::default();
+        let deserialize = |source: &str, enabled: bool| {
+            let val = context.eval_global("main", source).unwrap();
+            let mut deserializer = ValueDeserializer::from(val);
+            deserializer.array_like_objects(enabled);
+            Vec::<String>::deserialize(&mut deserializer)
+        };
+
+        assert_eq!(
+            vec!["a", "b", "c"],
+            deserialize("({0: 'a', 1: 'b', 2: 'c'})", true).unwrap()
+        );
+        assert_eq!(
+            vec!["a", "b"],
+            deserialize("({0: 'a', 1: 'b', length: 2})", true).unwrap()
+        );
+        assert!(deserialize("({0: 'a', 1: 'b', 2: 'c'})", false).is_err());
+
+        // Sparse keys, a mismatched length or other keys keep the object a map.
+        assert!(deserialize("({0: 'a', 2: 'c'})", true).is_err());
+        assert!(deserialize("({1: 'b', 2: 'c'})", true).is_err());
+        assert!(deserialize("({0: 'a', length: 3})", true).is_err());
+        assert!(deserialize("({0: 'a', name: 'b'})", true).is_err());
+
+        let val = context.eval_global("main", "({0: 'a', 2: 'c'})").unwrap();
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.array_like_objects(true);
+        let map = BTreeMap::<String, String>::deserialize(&mut deserializer).unwrap();
+        assert_eq!("c", map["2"]);
+    }
+
+    #[test]
+    fn test_skip_null_elements() {
+        let context = JSContextRef::default();
+        let deserialize = |source: &str, enabled: bool| {
+            let val = context.eval_global("main", source).unwrap();
+            let mut deserializer = ValueDeserializer::from(val);
+            deserializer.skip_null_elements(enabled);
+            Vec::<Vec<i32>>::deserialize(&mut deserializer)
+        };
+
+        assert_eq!(
+            vec![vec![1, 3], vec![]],
+            deserialize("[[1, null, 3], [undefined, null]]", true).unwrap()
+        );
+        assert_eq!(vec![vec![1, 4]], deserialize("[[1, , , 4]]", true).unwrap());
+        assert!(deserialize("[[1, null, 3]]", false).is_err());
+    }
+
+    #[test]
+    fn test_small_vec() {
+        let context = JSContextRef::default();
+        let small: SmallVec<[i32; 4]> =
+            deserialize_value(context.eval_global("main", "[1, 2, 3]").unwrap());
+        assert_eq!(&[1, 2, 3], small.as_slice());
+        assert!(!small.spilled());
+
+        // The array's length is used as a size hint, so spilled vectors are allocated once.
+        let large: SmallVec<[i32; 4]> = deserialize_value(
+            context
+                .eval_global("main", "Array.from({length: 10}, (_, i) => i)")
+                .unwrap(),
+        );
+        assert_eq!((0..10).collect::<Vec<_>>(), large.to_vec());
+        assert!(large.spilled());
+        assert_eq!(10, large.capacity());
+    }
+
+    #[test]
+    fn test_iterables() {
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global("main", "(function* () { yield 1; yield 2; yield 3; })()")
+            .unwrap();
+        assert_eq!(vec![1, 2, 3], deserialize_value::<Vec<i32>>(val));
+
+        let val = context.eval_global("main", "new Set(['a', 'b'])").unwrap();
+        assert_eq!(vec!["a", "b"], deserialize_value::<Vec<String>>(val));
+
+        let val = context
+            .eval_global("main", "({ *[Symbol.iterator]() { yield [1, 'one']; } })")
+            .unwrap();
+        assert_eq!(
+            vec![(1, "one".to_string())],
+            deserialize_value::<Vec<(u8, String)>>(val)
+        );
+    }
+
+    #[test]
+    fn test_sets() {
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global("main", "new Set([3, 1, 2, 1])")
+            .unwrap();
+        assert_eq!(
+            BTreeSet::from([1, 2, 3]),
+            deserialize_value::<BTreeSet<i32>>(val)
+        );
+
+        let val = context.eval_global("main", "new Set(['a', 'b'])").unwrap();
+        assert_eq!(
+            HashSet::from(["a".to_string(), "b".to_string()]),
+            deserialize_value::<HashSet<String>>(val)
+        );
+
+        let val = context.eval_global("main", "new Set([1, 'two'])").unwrap();
+        let err = BTreeSet::<i32>::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert!(err.to_string().contains("expected i32"), "{err}");
+
+        let val = context.eval_global("main", "new Set([1, 2, 3])").unwrap();
+        let err = BTreeSet::<i32>::deserialize(ValueDeserializer::from(val).max_iterator_len(2))
+            .unwrap_err();
+        assert_eq!(
+            "Set has 3 elements, more than the maximum of 2",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_iterable_errors() {
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global(
+                "main",
+                "(function* () { yield 1; throw new Error('broken'); })()",
+            )
+            .unwrap();
+        let err = Vec::<i32>::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert!(err.to_string().contains("broken"), "{err}");
+
+        let val = context
+            .eval_global(
+                "main",
+                "(function* () { for (let i = 0; ; i++) yield i; })()",
+            )
+            .unwrap();
+        let err = Vec::<i32>::deserialize(ValueDeserializer::from(val).max_iterator_len(100))
+            .unwrap_err();
+        assert_eq!("iterator produced more than 100 elements", err.to_string());
+
+        // Infinite generators are bounded without setting a limit.
+        let val = context
+            .eval_global("main", "(function* () { for (;;) yield; })()")
+            .unwrap();
+        let err = Vec::<IgnoredAny>::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert_eq!(
+            "iterator produced more than 1048576 elements",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_record_seen_keys() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct UserPatch {
+            #[serde(default)]
+            name: Option<String>,
+            #[serde(default)]
+            email: Option<String>,
+            #[serde(default)]
+            address: Option<BTreeMap<String, String>>,
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global("main", "({name: null, address: {city: 'Oslo'}})")
+            .unwrap();
+        let mut deserializer = ValueDeserializer::from(val);
+        assert_eq!(None, deserializer.seen_keys());
+        deserializer.record_seen_keys(true);
+        let patch = UserPatch::deserialize(&mut deserializer).unwrap();
+        assert_eq!(None, patch.name);
+        assert_eq!(None, patch.email);
+
+        let seen = deserializer.seen_keys().unwrap();
+        assert_eq!(
+            HashSet::from(["name".to_string(), "address".to_string()]),
+            *seen
+        );
+
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.intern_keys(true).record_seen_keys(true);
+        UserPatch::deserialize(&mut deserializer).unwrap();
+        assert_eq!(2, deserializer.seen_keys().unwrap().len());
+    }
+
+    #[test]
+    fn test_max_nodes() {
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global("main", "Array.from({length: 1000}, (_, i) => i)")
+            .unwrap();
+        let err = Vec::<i32>::deserialize(ValueDeserializer::from(val).max_nodes(100)).unwrap_err();
+        assert_eq!("more than 100 values to deserialize", err.to_string());
+        let all = Vec::<i32>::deserialize(ValueDeserializer::from(val).max_nodes(1000)).unwrap();
+        assert_eq!(1000, all.len());
+
+        // `a`, its two elements, `b` and `c`.
+        let val = context
+            .eval_global("main", "({a: [1, 2], b: {c: 3}})")
+            .unwrap();
+        let value = serde_json::Value::deserialize(ValueDeserializer::from(val).max_nodes(5));
+        assert!(value.is_ok());
+        let value = serde_json::Value::deserialize(ValueDeserializer::from(val).max_nodes(4));
+        assert!(value.is_err());
+    }
+
+    #[test]
+    fn test_hook() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Order {
+            item: String,
+            price: u32,
+            tax: f64,
+            currency: String,
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global(
+                "main",
+                r#"
+                class Money {
+                    constructor(cents) { this.cents = cents; }
+                    valueOf() { return this.cents; }
+                }
+                class Currency {}
+                ({item: 'book', price: new Money(1250), tax: new Money(1.5), currency: new Currency()})
+                "#,
+            )
+            .unwrap();
+        let class_name = |value: &JSValueRef| {
+            let name = value
+                .get_property("constructor")
+                .ok()?
+                .get_property("name")
+                .ok()?;
+            name.as_str().ok().map(ToString::to_string)
+        };
+
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.hook(|value| match class_name(value)?.as_str() {
+            "Money" => {
+                let value_of = value.get_property("valueOf").ok()?;
+                value_of.call(value, &[]).ok().map(HookAction::Value)
+            }
+            "Currency" => Some(HookAction::String("EUR".to_string())),
+            _ => None,
+        });
+        assert_eq!(
+            Order {
+                item: "book".to_string(),
+                price: 1250,
+                tax: 1.5,
+                currency: "EUR".to_string(),
+            },
+            Order::deserialize(&mut deserializer).unwrap()
+        );
+
+        let val = context.eval_global("main", "[1.0, 'x']").unwrap();
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.hook(|value| value.is_str().then_some(HookAction::Number(7.0)));
+        assert_eq!((1, 7), <(u8, u8)>::deserialize(&mut deserializer).unwrap());
+    }
+
+    #[test]
+    fn test_json_value_preserves_key_order() {
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global("main", "({z: 1, a: 2, m: {y: true, b: false}})")
+            .unwrap();
+
+        let actual = deserialize_value::<serde_json::Value>(val);
+
+        let object = actual.as_object().unwrap();
+        assert_eq!(vec!["z", "a", "m"], object.keys().collect::<Vec<_>>());
+        let nested = object["m"].as_object().unwrap();
+        assert_eq!(vec!["y", "b"], nested.keys().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_deny_unknown_fields_names_the_key() {
+        #[derive(Deserialize, Debug)]
+        #[serde(deny_unknown_fields)]
+        #[allow(dead_code)]
+        struct Config {
+            user_name: String,
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global("main", "({user_name: 'javy', userName: 'javy'})")
+            .unwrap();
+        let err = Config::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+
+        // Keys are handed to serde as they appear on the object, so the error names the key
+        // exactly as it was written in JavaScript.
+        assert_eq!(
+            "unknown field `userName`, expected `user_name`",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_error_objects() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct JsError {
+            name: String,
+            message: String,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct DetailedJsError {
+            name: String,
+            stack: Option<String>,
+            code: Option<u32>,
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global(
+                "main",
+                "(() => { try { throw new TypeError('bad input') } catch (e) { return e } })()",
+            )
+            .unwrap();
+        assert_eq!(
+            JsError {
+                name: "TypeError".to_string(),
+                message: "bad input".to_string(),
+            },
+            deserialize_value::<JsError>(val)
+        );
+
+        let val = context
+            .eval_global(
+                "main",
+                "(() => { try { const e = new Error('failed'); e.name = 'CustomError'; e.code = 42; throw e } catch (e) { return e } })()",
+            )
+            .unwrap();
+        let actual = deserialize_value::<DetailedJsError>(val);
+        assert_eq!("CustomError", actual.name);
+        assert!(actual.stack.unwrap().contains("at "));
+        assert_eq!(Some(42), actual.code);
+    }
+
+    #[test]
+    fn test_circular_references() {
+        let context = JSContextRef::default();
+        let deserialize = |source: &str| {
+            let val = context.eval_global("main", source).unwrap();
+            serde_json::Value::deserialize(&mut ValueDeserializer::from(val))
+        };
+
+        let err = deserialize("var a = {name: 'a'}; a.self = a; a").unwrap_err();
+        assert_eq!("circular reference", err.to_string());
+        let err = deserialize("var b = [1]; b.push({items: b}); b").unwrap_err();
+        assert_eq!("circular reference", err.to_string());
+
+        // The same object can appear more than once as long as it doesn't contain itself.
+        let shared = deserialize("var c = {n: 1}; ({first: c, second: [c, c]})").unwrap();
+        assert_eq!(shared["first"], shared["second"][1]);
+    }
+
+    #[test]
+    fn test_trim_strings() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Padded {
+            text: String,
+            initial: char,
+        }
+
+        let context = JSContextRef::default();
+        let val = context.eval_global("main", "'  hi  '").unwrap();
+        assert_eq!("  hi  ", deserialize_value::<String>(val));
+        let trimmed = String::deserialize(ValueDeserializer::from(val).trim_strings(true)).unwrap();
+        assert_eq!("hi", trimmed);
+
+        let val = context
+            .eval_global("main", "({' text ': 1, text: '\\n a b\\t', initial: ' '})")
+            .unwrap();
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.trim_strings(true);
+        let padded = Padded::deserialize(&mut deserializer).unwrap();
+        assert_eq!(
+            Padded {
+                text: "a b".to_string(),
+                initial: ' '
+            },
+            padded
+        );
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_events() {
+        use std::fmt::{self, Write};
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        /// Records every event as a line of `name=value` pairs.
+        #[derive(Clone, Default)]
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+
+        struct Line<'a>(&'a mut String);
+
+        impl Visit for Line<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                write!(self.0, " {}={:?}", field.name(), value).unwrap();
+            }
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut line = String::new();
+                event.record(&mut Line(&mut line));
+                self.0.lock().unwrap().push(line);
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct User {
+            name: String,
+            age: u32,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Account {
+            user: User,
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global("main", "({user: {name: 'Ada', age: 'old'}})")
+            .unwrap();
+        let recorder = Recorder::default();
+        let result = tracing::subscriber::with_default(recorder.clone(), || {
+            Account::deserialize(&mut ValueDeserializer::from(val))
+        });
+        assert!(result.is_err());
+
+        let events = recorder.0.lock().unwrap();
+        let has_event = |parts: &[&str]| {
+            events
+                .iter()
+                .any(|line| parts.iter().all(|part| line.contains(part)))
+        };
+        assert!(
+            has_event(&["deserializing field", "key=\"user\"", "depth=1"]),
+            "{events:?}"
+        );
+        assert!(
+            has_event(&["deserializing field", "key=\"age\"", "depth=2"]),
+            "{events:?}"
+        );
+        assert!(
+            has_event(&["visiting value", "kind=\"string\""]),
+            "{events:?}"
+        );
+        let failures = events
+            .iter()
+            .filter(|line| line.contains("deserialization failed"))
+            .count();
+        assert_eq!(2, failures, "{events:?}");
+        drop(events);
+
+        // Interned keys and `Map` keys are read differently, but are traced the same way.
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Profile {
+            user: User,
+            scores: BTreeMap<String, u32>,
+        }
+
+        let val = context
+            .eval_global(
+                "main",
+                "({user: {name: 'Ada', age: 36}, scores: new Map([['math', 1]])})",
+            )
+            .unwrap();
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            Profile::deserialize(ValueDeserializer::from(val).intern_keys(true))
+        })
+        .unwrap();
+
+        let events = recorder.0.lock().unwrap();
+        let has_event = |parts: &[&str]| {
+            events
+                .iter()
+                .any(|line| parts.iter().all(|part| line.contains(part)))
+        };
+        for (key, depth) in [("user", 1), ("age", 2), ("scores", 1), ("math", 2)] {
+            assert!(
+                has_event(&[
+                    "deserializing field",
+                    &format!("key={key:?}"),
+                    &format!("depth={depth}")
+                ]),
+                "{key}: {events:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_transparent_newtypes() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(transparent)]
+        struct Id(u64);
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(transparent)]
+        struct Name {
+            value: String,
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct User {
+            id: Id,
+            name: Name,
+            friends: Vec<Id>,
+        }
+
+        let context = JSContextRef::default();
+        let val = context.eval_global("main", "42").unwrap();
+        assert_eq!(Id(42), deserialize_value::<Id>(val));
+
+        let val = context
+            .eval_global("main", "({id: 1, name: 'Ada', friends: [2, 3]})")
+            .unwrap();
+        assert_eq!(
+            User {
+                id: Id(1),
+                name: Name {
+                    value: "Ada".to_string()
+                },
+                friends: vec![Id(2), Id(3)],
+            },
+            deserialize_value::<User>(val)
+        );
+
+        // The inner value isn't expected to be wrapped in another level.
+        let val = context.eval_global("main", "({value: 42})").unwrap();
+        assert!(Id::deserialize(&mut ValueDeserializer::from(val)).is_err());
+    }
+
+    #[test]
+    fn test_map_string_keys_are_not_transformed() {
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global("main", "new Map([[' Key ', ' Value '], ['B', 'b']])")
+            .unwrap();
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer
+            .trim_strings(true)
+            .normalize_string_values(Some(Case::Lower))
+            .max_nodes(2);
+        let map = BTreeMap::<String, String>::deserialize(&mut deserializer).unwrap();
+        assert_eq!(
+            BTreeMap::from([
+                (" Key ".to_string(), "value".to_string()),
+                ("B".to_string(), "b".to_string())
+            ]),
+            map
+        );
+    }
+
+    #[test]
+    fn test_keys_are_passed_through_to_renamed_fields() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(rename_all = "camelCase")]
+        struct Profile {
+            display_name: String,
+            is_admin: bool,
+            login_count: u32,
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global(
+                "main",
+                "({displayName: 'Ada', isAdmin: true, loginCount: 3, display_name: 'ignored'})",
+            )
+            .unwrap();
+        assert_eq!(
+            Profile {
+                display_name: "Ada".to_string(),
+                is_admin: true,
+                login_count: 3,
+            },
+            deserialize_value::<Profile>(val)
+        );
+    }
+
+    #[test]
+    fn test_normalize_string_values() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        enum Status {
+            Active,
+            #[serde(rename = "active")]
+            Lowercase,
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Account {
+            #[serde(rename = "Email")]
+            email: String,
+            tags: Vec<String>,
+            status: Status,
+            extra: HashMap<String, String>,
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global(
+                "main",
+                "({Email: 'Ada@Example.COM', tags: ['A', 'b'], status: 'Active', extra: {Key: 'VALUE'}})",
+            )
+            .unwrap();
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.normalize_string_values(Some(Case::Lower));
+        let account = Account::deserialize(&mut deserializer).unwrap();
+        assert_eq!(
+            Account {
+                email: "ada@example.com".to_string(),
+                tags: vec!["a".to_string(), "b".to_string()],
+                status: Status::Active,
+                extra: HashMap::from([("Key".to_string(), "value".to_string())]),
+            },
+            account
+        );
+
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer
+            .normalize_string_values(Some(Case::Lower))
+            .normalize_enum_tags(true);
+        let account = Account::deserialize(&mut deserializer).unwrap();
+        assert_eq!(Status::Lowercase, account.status);
+
+        let val = context.eval_global("main", "'Straße'").unwrap();
+        let upper = String::deserialize(
+            ValueDeserializer::from(val).normalize_string_values(Some(Case::Upper)),
+        )
+        .unwrap();
+        assert_eq!("STRASSE", upper);
+    }
+
+    #[test]
+    fn test_skip_undefined_properties() {
+        let context = JSContextRef::default();
+        let source = "({a: undefined, b: 1, get c() { return undefined; }})";
+
+        let val = context.eval_global("main", source).unwrap();
+        let err = BTreeMap::<String, i32>::deserialize(&mut ValueDeserializer::from(val));
+        assert!(err.is_err());
+
+        for intern_keys in [false, true] {
+            let val = context.eval_global("main", source).unwrap();
+            let mut deserializer = ValueDeserializer::from(val);
+            deserializer
+                .skip_undefined_properties(true)
+                .intern_keys(intern_keys);
+            let map = BTreeMap::<String, i32>::deserialize(&mut deserializer).unwrap();
+            assert_eq!(BTreeMap::from([("b".to_string(), 1)]), map);
+        }
+    }
+
+    #[test]
+    fn test_proxy_traps_are_invoked() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Profile {
+            name: String,
+            visits: u32,
+        }
+
+        let context = JSContextRef::default();
+        // Keys are enumerated from the target while values come from the `get` trap.
+        let val = context
+            .eval_global(
+                "main",
+                r#"new Proxy({name: "", visits: 0}, {
+                    get(target, key) { return key === "name" ? "proxied" : 7; },
+                })"#,
+            )
+            .unwrap();
+        let profile: Profile = deserialize_value(val);
+        assert_eq!(
+            Profile {
+                name: "proxied".to_string(),
+                visits: 7
+            },
+            profile
+        );
+
+        // Keys that only exist through the `ownKeys` and `getOwnPropertyDescriptor` traps are
+        // enumerated as well, like with `Object.keys`.
+        let val = context
+            .eval_global(
+                "main",
+                r#"new Proxy({}, {
+                    ownKeys() { return ["a", "b"]; },
+                    getOwnPropertyDescriptor() { return {enumerable: true, configurable: true}; },
+                    get(target, key) { return key.charCodeAt(0); },
+                })"#,
+            )
+            .unwrap();
+        let map: BTreeMap<String, u32> = deserialize_value(val);
+        assert_eq!(
+            BTreeMap::from([("a".to_string(), 97), ("b".to_string(), 98)]),
+            map
+        );
+
+        let val = context
+            .eval_global(
+                "main",
+                "new Proxy([1, 2], { get: (t, k) => k === 'length' ? 2 : Number(k) * 10 })",
+            )
+            .unwrap();
+        assert_eq!(vec![0, 10], deserialize_value::<Vec<u32>>(val));
+    }
+
+    #[test]
+    fn test_ignored_fields_are_skipped() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Narrow {
+            id: u32,
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global(
+                "main",
+                "var a = {id: 1, tags: new WeakSet(), nested: {deep: [1, 2]}}; a.self = a; a",
+            )
+            .unwrap();
+        // Ignored values aren't read, so they don't fail like cycles and weak collections do.
+        let narrow = Narrow::deserialize(&mut ValueDeserializer::from(val)).unwrap();
+        assert_eq!(Narrow { id: 1 }, narrow);
+
+        let val = context
+            .eval_global("main", "({id: 1, nested: {deep: 1}})")
+            .unwrap();
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.allowed_keys(["id", "nested"], DisallowedKeys::Error);
+        let err = Narrow::deserialize(&mut deserializer).unwrap_err();
+        assert_eq!("Key `deep` is not allowed", err.to_string());
+    }
+
+    #[test]
+    fn test_getters_are_invoked() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Computed {
+            x: u32,
+            label: String,
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global(
+                "main",
+                r#"
+                var o = {label: 'answer'};
+                Object.defineProperty(o, 'x', {get() { return 42; }, enumerable: true});
+                o
+                "#,
+            )
+            .unwrap();
+        assert_eq!(
+            Computed {
+                x: 42,
+                label: "answer".to_string()
+            },
+            deserialize_value::<Computed>(val)
+        );
+
+        let val = context
+            .eval_global("main", "({get doubled() { return this.n * 2; }, n: 4})")
+            .unwrap();
+        let map = deserialize_value::<BTreeMap<String, u32>>(val);
+        assert_eq!(Some(&8), map.get("doubled"));
+    }
+
+    #[test]
+    fn test_enums() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        enum Command {
+            Stop,
+            Move(i32),
+            Resize(u32, u32),
+            Rename { name: String },
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global(
+                "main",
+                "['Stop', {Stop: null}, {Move: -3}, {Resize: [2, 4]}, {Rename: {name: 'b'}}]",
+            )
+            .unwrap();
+        assert_eq!(
+            vec![
+                Command::Stop,
+                Command::Stop,
+                Command::Move(-3),
+                Command::Resize(2, 4),
+                Command::Rename {
+                    name: "b".to_string()
+                },
+            ],
+            deserialize_value::<Vec<Command>>(val)
+        );
+
+        let val = context.eval_global("main", "'Jump'").unwrap();
+        let err = Command::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert!(err.to_string().contains("unknown variant `Jump`"), "{err}");
+        let val = context
+            .eval_global("main", "({Stop: null, Move: 1})")
+            .unwrap();
+        let err = Command::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert_eq!(
+            "expected a string, an index or an object with a single key, found object",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_enum_other_variant() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(tag = "type")]
+        enum Event {
+            Click {
+                x: i32,
+            },
+            #[serde(other)]
+            Unknown,
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(tag = "kind", content = "data")]
+        enum Message {
+            Text(String),
+            #[serde(other)]
+            Unsupported,
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global("main", "[{type: 'Click', x: 1}, {type: 'Scroll', y: 2}]")
+            .unwrap();
+        assert_eq!(
+            vec![Event::Click { x: 1 }, Event::Unknown],
+            deserialize_value::<Vec<Event>>(val)
+        );
+
+        let val = context
+            .eval_global("main", "[{kind: 'Text', data: 'hi'}, {kind: 'Image'}]")
+            .unwrap();
+        assert_eq!(
+            vec![Message::Text("hi".to_string()), Message::Unsupported],
+            deserialize_value::<Vec<Message>>(val)
+        );
+    }
+
+    #[test]
+    fn test_untagged_enum_by_key_presence() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Circle {
+            radius: f64,
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Square {
+            side: u32,
+            label: Option<String>,
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(untagged)]
+        enum Shape {
+            Circle { circle: Circle },
+            Square { square: Square },
+        }
+
+        let context = JSContextRef::default();
+        let deserialize = |source: &str| {
+            let val = context.eval_global("main", source).unwrap();
+            Vec::<Shape>::deserialize(&mut ValueDeserializer::from(val))
+        };
+
+        assert_eq!(
+            vec![
+                Shape::Circle {
+                    circle: Circle { radius: 1.5 }
+                },
+                Shape::Square {
+                    square: Square {
+                        side: 2,
+                        label: None
+                    }
+                },
+                Shape::Square {
+                    square: Square {
+                        side: 3,
+                        label: Some("big".to_string())
+                    }
+                },
+            ],
+            deserialize(
+                "[{circle: {radius: 1.5}}, {square: {side: 2}}, {square: {side: 3, label: 'big'}}]"
+            )
+            .unwrap()
+        );
+
+        let err = deserialize("[{triangle: {sides: 3}}]").unwrap_err();
+        assert_eq!(
+            "data did not match any variant of untagged enum Shape",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_weak_collections_are_rejected() {
+        let context = JSContextRef::default();
+        let deserialize = |source: &str| {
+            let val = context.eval_global("main", source).unwrap();
+            serde_json::Value::deserialize(&mut ValueDeserializer::from(val))
+        };
+
+        let err =
+            deserialize("var key = {}; var map = new WeakMap(); map.set(key, 1); map").unwrap_err();
+        assert_eq!("cannot deserialize WeakMap", err.to_string());
+        let err = deserialize("({items: new WeakSet([{}])})").unwrap_err();
+        assert_eq!("cannot deserialize WeakSet", err.to_string());
+
+        // Plain empty objects are still deserialized as empty maps.
+        assert_eq!(serde_json::json!({}), deserialize("({})").unwrap());
+    }
+
+    #[test]
+    fn test_str_is_strict() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct User {
+            name: String,
+        }
+
+        let context = JSContextRef::default();
+        assert_eq!(
+            "javy",
+            deserialize_value::<String>(context.value_from_str("javy").unwrap())
+        );
+
+        for (source, type_name) in [("42", "number"), ("true", "boolean"), ("null", "null")] {
+            let val = context.eval_global("main", source).unwrap();
+            let err = String::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+            assert_eq!(
+                format!("expected a string, found {type_name}"),
+                err.to_string()
+            );
+        }
+
+        let val = context.eval_global("main", "({name: 42})").unwrap();
+        let err = User::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert_eq!("expected a string, found number", err.to_string());
+    }
+
+    #[test]
+    fn test_allowed_keys() {
+        let context = JSContextRef::default();
+        let deserialize = |source: &str, disallowed: DisallowedKeys| {
+            let val = context.eval_global("main", source).unwrap();
+            let mut deserializer = ValueDeserializer::from(val);
+            deserializer.allowed_keys(["name", "tags"], disallowed);
+            serde_json::Value::deserialize(&mut deserializer)
+        };
+
+        let source = "({name: 'javy', tags: [{name: 'wasm', id: 1}], admin: true})";
+        let err = deserialize(source, DisallowedKeys::Error).unwrap_err();
+        assert_eq!("Key `id` is not allowed", err.to_string());
+
+        let actual = deserialize(source, DisallowedKeys::Skip).unwrap();
+        assert_eq!(
+            serde_json::json!({"name": "javy", "tags": [{"name": "wasm"}]}),
+            actual
+        );
+    }
+
+    #[test]
+    fn test_allowed_keys_block_proto() {
+        let context = JSContextRef::default();
+        // `JSON.parse` defines `__proto__` as a regular own property.
+        let source = r#"JSON.parse('{"name": "javy", "__proto__": {"admin": true}}')"#;
+
+        let val = context.eval_global("main", source).unwrap();
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.allowed_keys(["name", "admin"], DisallowedKeys::Error);
+        let err =
+            BTreeMap::<String, serde_json::Value>::deserialize(&mut deserializer).unwrap_err();
+        assert_eq!("Key `__proto__` is not allowed", err.to_string());
+
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.allowed_keys(["name", "admin"], DisallowedKeys::Skip);
+        let actual = BTreeMap::<String, serde_json::Value>::deserialize(&mut deserializer).unwrap();
+        assert_eq!(vec!["name"], actual.keys().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_deserialize_rows() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Row {
+            id: u32,
+            name: String,
+            tags: BTreeMap<String, bool>,
+        }
+
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global(
+                "main",
+                "[{id: 1, name: 'a', tags: {x: true}}, {id: 2, name: 'b', tags: {}}, {name: 'c', id: 3, tags: {y: false}}]",
+            )
+            .unwrap();
+        let rows = ValueDeserializer::from(val)
+            .deserialize_rows::<Row>()
+            .unwrap();
+        assert_eq!(
+            vec![
+                Row {
+                    id: 1,
+                    name: "a".to_string(),
+                    tags: BTreeMap::from([("x".to_string(), true)]),
+                },
+                Row {
+                    id: 2,
+                    name: "b".to_string(),
+                    tags: BTreeMap::new(),
+                },
+                Row {
+                    id: 3,
+                    name: "c".to_string(),
+                    tags: BTreeMap::from([("y".to_string(), false)]),
+                },
+            ],
+            rows
+        );
+
+        let val = context.eval_global("main", "[{id: 1, secret: 2}]").unwrap();
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.allowed_keys(["id"], DisallowedKeys::Error);
+        let err = deserializer
+            .deserialize_rows::<BTreeMap<String, u32>>()
+            .unwrap_err();
+        assert_eq!("Key `secret` is not allowed", err.to_string());
+
+        let val = context.eval_global("main", "({id: 1})").unwrap();
+        let err = ValueDeserializer::from(val)
+            .deserialize_rows::<Row>()
+            .unwrap_err();
+        assert_eq!("expected an array, found object", err.to_string());
+    }
+
+    #[test]
+    fn test_intern_keys() {
+        let context = JSContextRef::default();
+        let val = context
+            .eval_global(
+                "main",
+                "[{a: 1, b: {a: 2}}, {b: {c: 3}, a: 4}, {'ключ': 5}]",
+            )
+            .unwrap();
+        let expected = deserialize_value::<serde_json::Value>(val);
+
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.intern_keys(true);
+        assert_eq!(
+            expected,
+            serde_json::Value::deserialize(&mut deserializer).unwrap()
+        );
+        // `a`, `b`, `c` and `ключ` are each converted once.
+        assert_eq!(4, deserializer.key_cache.as_ref().unwrap().len());
+
+        let mut deserializer = ValueDeserializer::from(val);
+        deserializer.intern_keys(true);
+        let rows = deserializer
+            .deserialize_rows::<BTreeMap<String, serde_json::Value>>()
+            .unwrap();
+        assert_eq!(5, rows[2]["ключ"]);
+        // Interned keys outlive the call to `deserialize_rows`.
+        assert_eq!(4, deserializer.key_cache.as_ref().unwrap().len());
+    }
 }
@@ -1,23 +1,120 @@
+use crate::js_binding::exception::Exception;
 use std::{error, fmt};
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
+    /// QuickJS stopped executing JavaScript, for example because an interrupt handler asked it
+    /// to.
+    Interrupted(anyhow::Error),
+    /// The JavaScript value doesn't have the type expected by the Rust value.
+    TypeMismatch(String),
     Custom(anyhow::Error),
 }
 
+/// The category of an [`Error`], see [`Error::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Interrupted,
+    TypeMismatch,
+    Other,
+}
+
+impl Error {
+    /// Returns the category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Interrupted(_) => ErrorKind::Interrupted,
+            Error::TypeMismatch(_) => ErrorKind::TypeMismatch,
+            Error::Custom(_) => ErrorKind::Other,
+        }
+    }
+
+    /// Returns `true` if retrying could succeed, for example with a larger execution budget.
+    ///
+    /// Errors caused by the shape of the data, like type mismatches, will fail the same way
+    /// every time.
+    pub fn is_recoverable(&self) -> bool {
+        self.kind() == ErrorKind::Interrupted
+    }
+}
+
 impl error::Error for Error {}
 
 impl fmt::Display for Error {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Error::Custom(e) => formatter.write_str(&e.to_string()),
+            Error::Interrupted(e) | Error::Custom(e) => formatter.write_str(&e.to_string()),
+            Error::TypeMismatch(msg) => formatter.write_str(msg),
         }
     }
 }
 
 impl From<anyhow::Error> for Error {
     fn from(e: anyhow::Error) -> Self {
-        Error::Custom(e)
+        if e.downcast_ref::<Exception>()
+            .is_some_and(Exception::is_interrupted)
+        {
+            Error::Interrupted(e)
+        } else {
+            Error::Custom(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, ErrorKind};
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use serde::Deserialize;
+
+    #[test]
+    fn test_interrupt_is_recoverable() {
+        let context = JSContextRef::default();
+        let err: Error = context
+            .eval_global_with_fuel("main", "for (;;) {}", 10)
+            .unwrap_err()
+            .into();
+        assert_eq!(ErrorKind::Interrupted, err.kind());
+        assert!(err.is_recoverable());
+    }
+
+    #[test]
+    fn test_thrown_internal_error_is_not_an_interrupt() {
+        let context = JSContextRef::default();
+        for source in [
+            "throw new InternalError('interrupted')",
+            "for (let i = 0; i < 10; i++) {} throw new InternalError('interrupted')",
+        ] {
+            let err: Error = context
+                .eval_global_with_fuel("main", source, 1000)
+                .unwrap_err()
+                .into();
+            assert_eq!(ErrorKind::Other, err.kind(), "{source}");
+            assert!(!err.is_recoverable());
+        }
+    }
+
+    #[test]
+    fn test_type_mismatch_is_not_recoverable() {
+        let context = JSContextRef::default();
+        let val = context.value_from_i32(42).unwrap();
+        let err = String::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert_eq!(ErrorKind::TypeMismatch, err.kind());
+        assert!(!err.is_recoverable());
+        assert_eq!("expected a string, found number", err.to_string());
+
+        let val = context.value_from_i32(1).unwrap();
+        let err = bool::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert_eq!(ErrorKind::TypeMismatch, err.kind());
+    }
+
+    #[test]
+    fn test_other_errors_are_not_recoverable() {
+        let context = JSContextRef::default();
+        let err: Error = context.eval_global("main", "foo(").unwrap_err().into();
+        assert_eq!(ErrorKind::Other, err.kind());
+        assert!(!err.is_recoverable());
     }
 }
@@ -1,5 +1,7 @@
+pub mod adapters;
 pub mod de;
 pub mod err;
+pub mod schema;
 pub mod ser;
 
 use super::js_binding::value::JSValueRef;
@@ -23,6 +25,7 @@ mod tests {
     use serde::de::DeserializeOwned;
     use serde::{Deserialize, Serialize};
     use std::collections::BTreeMap;
+    use std::net::{IpAddr, SocketAddr};
 
     quickcheck! {
         fn test_str(expected: String) -> Result<bool> {
@@ -30,6 +33,11 @@ mod tests {
             Ok(expected == actual)
         }
 
+        fn test_char(expected: char) -> Result<bool> {
+            let actual = do_roundtrip::<_, char>(&expected);
+            Ok(expected == actual)
+        }
+
         fn test_u8(expected: u8) -> Result<bool> {
             let actual = do_roundtrip::<_, u8>(&expected);
             Ok(expected == actual)
@@ -264,6 +272,158 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_chars_are_single_character_strings() -> Result<()> {
+        let context = JSContextRef::default();
+        for (c, utf16) in [('é', &[0xe9][..]), ('😀', &[0xd83d, 0xde00][..])] {
+            let mut serializer = ValueSerializer::from_context(&context)?;
+            c.serialize(&mut serializer)?;
+            let val = serializer.value;
+            assert_eq!(utf16.len(), val.utf16_len()?);
+            let char_code_at = val.get_property("charCodeAt")?;
+            for (index, unit) in utf16.iter().enumerate() {
+                let index = context.value_from_i32(index as i32)?;
+                assert_eq!(*unit, char_code_at.call(&val, &[index])?.as_i32_unchecked());
+            }
+            assert_eq!(c, do_roundtrip::<_, char>(&c));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_js_maps() -> Result<()> {
+        #[derive(Serialize)]
+        struct Config {
+            name: &'static str,
+        }
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Flattened {
+            name: String,
+            #[serde(flatten)]
+            extra: BTreeMap<String, u32>,
+        }
+
+        let context = JSContextRef::default();
+        let global = context.global_object()?;
+        let mut ordered = serde_json::Map::new();
+        for (key, value) in [("b", 1), ("10", 2), ("a", 3), ("2", 4)] {
+            ordered.insert(key.to_string(), value.into());
+        }
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        ordered.serialize(&mut serializer)?;
+        global.set_property("object", serializer.value)?;
+        let keys = context.eval_global("main", "Object.keys(object).join()")?;
+        assert_eq!("2,10,b,a", keys.as_str()?);
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.js_maps(true);
+        ordered.serialize(&mut serializer)?;
+        let map = serializer.value;
+        global.set_property("map", map)?;
+        let keys = context.eval_global("main", "map instanceof Map && [...map.keys()].join()")?;
+        assert_eq!("b,10,a,2", keys.as_str()?);
+        let round_trip = serde_json::Map::deserialize(&mut ValueDeserializer::from(map))?;
+        assert_eq!(ordered, round_trip);
+
+        let numbered = BTreeMap::from([(3_u32, "c"), (1, "a")]);
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.js_maps(true);
+        numbered.serialize(&mut serializer)?;
+        let map = serializer.value;
+        global.set_property("numbered", map)?;
+        let keys = context.eval_global("main", "[...numbered.keys()].map(k => typeof k).join()")?;
+        assert_eq!("number,number", keys.as_str()?);
+        let round_trip = BTreeMap::<u32, String>::deserialize(&mut ValueDeserializer::from(map))?;
+        assert_eq!(
+            BTreeMap::from([(1, "a".to_string()), (3, "c".to_string())]),
+            round_trip
+        );
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.js_maps(true);
+        Config { name: "javy" }.serialize(&mut serializer)?;
+        assert_eq!("javy", serializer.value.get_property("name")?.as_str()?);
+
+        // serde serializes structs with flattened fields as maps.
+        let flattened = Flattened {
+            name: "javy".to_string(),
+            extra: BTreeMap::from([("version".to_string(), 3)]),
+        };
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.js_maps(true);
+        flattened.serialize(&mut serializer)?;
+        let map = serializer.value;
+        global.set_property("flattened", map)?;
+        let entries = context.eval_global(
+            "main",
+            "flattened instanceof Map && JSON.stringify([...flattened])",
+        )?;
+        assert_eq!(r#"[["name","javy"],["version",3]]"#, entries.as_str()?);
+        let round_trip = Flattened::deserialize(&mut ValueDeserializer::from(map))?;
+        assert_eq!(flattened, round_trip);
+        Ok(())
+    }
+
+    #[test]
+    fn test_enum_as_index() -> Result<()> {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        enum Status {
+            Active,
+            Suspended,
+            Deleted,
+            Renamed(String),
+        }
+
+        let context = JSContextRef::default();
+        let expected = vec![
+            Status::Active,
+            Status::Deleted,
+            Status::Suspended,
+            Status::Renamed("b".to_string()),
+        ];
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.enum_as_index(true);
+        expected.serialize(&mut serializer)?;
+        assert_eq!(
+            r#"[0,2,1,{"Renamed":"b"}]"#,
+            serializer.value.to_json_string()?
+        );
+
+        let actual = Vec::<Status>::deserialize(&mut ValueDeserializer::from(serializer.value))?;
+        assert_eq!(expected, actual);
+
+        let val = context.eval_global("main", "7")?;
+        let err = Status::deserialize(&mut ValueDeserializer::from(val)).unwrap_err();
+        assert!(
+            err.to_string().contains("variant index 0 <= i < 4"),
+            "{err}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_addresses_are_strings() -> Result<()> {
+        let context = JSContextRef::default();
+        for address in ["127.0.0.1", "::1", "2001:db8::8a2e:370:7334"] {
+            let ip: IpAddr = address.parse()?;
+            let mut serializer = ValueSerializer::from_context(&context)?;
+            ip.serialize(&mut serializer)?;
+            assert_eq!(address, serializer.value.as_str()?);
+            assert_eq!(ip, do_roundtrip::<_, IpAddr>(&ip));
+        }
+
+        for address in ["127.0.0.1:8080", "[::1]:443"] {
+            let socket: SocketAddr = address.parse()?;
+            let mut serializer = ValueSerializer::from_context(&context)?;
+            socket.serialize(&mut serializer)?;
+            assert_eq!(address, serializer.value.as_str()?);
+            assert_eq!(socket, do_roundtrip::<_, SocketAddr>(&socket));
+        }
+        Ok(())
+    }
+
     fn do_roundtrip<E, A>(expected: &E) -> A
     where
         E: Serialize,
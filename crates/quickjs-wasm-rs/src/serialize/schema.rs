@@ -0,0 +1,261 @@
+use super::de::type_name;
+use super::err::{Error, Result};
+use crate::js_binding::value::JSValueRef;
+use anyhow::anyhow;
+
+/// `Schema` is a lightweight description of the structure expected from a JavaScript value.
+///
+/// It's used with [`Deserializer::validate`](crate::Deserializer::validate) to check a value
+/// before deserializing it. Unlike a failed deserialization, which stops at the first problem, a
+/// validation error lists every missing, unexpected and mistyped field along with its path, like
+/// `items[1].name`.
+///
+/// # Example
+///
+/// ```
+/// let schema = Schema::object([
+///     ("name", Schema::String),
+///     ("tags", Schema::Array(Box::new(Schema::String))),
+///     ("age", Schema::Optional(Box::new(Schema::Integer))),
+/// ]);
+/// let mut deserializer = Deserializer::from(value);
+/// let user = User::deserialize(deserializer.validate(&schema)?)?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    /// Accepts any value, including `undefined`.
+    Any,
+    Null,
+    Boolean,
+    Number,
+    /// A number without a fractional part.
+    Integer,
+    String,
+    /// An array whose elements all match the schema.
+    Array(Box<Schema>),
+    /// An object with the given fields. Fields whose schema accepts `undefined`, like
+    /// [`Schema::Optional`], can be left out. Other own enumerable properties are rejected
+    /// unless `allow_unknown_fields` is set.
+    Object {
+        fields: Vec<(String, Schema)>,
+        allow_unknown_fields: bool,
+    },
+    /// Accepts `null`, `undefined` or a value matching the schema.
+    Optional(Box<Schema>),
+}
+
+impl Schema {
+    /// Creates a [`Schema::Object`] with the given fields that rejects unknown fields.
+    pub fn object<I, K>(fields: I) -> Self
+    where
+        I: IntoIterator<Item = (K, Schema)>,
+        K: Into<String>,
+    {
+        Schema::Object {
+            fields: fields
+                .into_iter()
+                .map(|(name, schema)| (name.into(), schema))
+                .collect(),
+            allow_unknown_fields: false,
+        }
+    }
+
+    /// Checks that `value` matches the schema, returning an error listing every violation if it
+    /// doesn't.
+    pub fn validate(&self, value: &JSValueRef) -> Result<()> {
+        let mut violations = Vec::new();
+        self.check(value, &mut String::new(), &mut violations)?;
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(Error::Custom(anyhow!(
+            "value does not match the schema:\n{}",
+            violations.join("\n")
+        )))
+    }
+
+    fn accepts_undefined(&self) -> bool {
+        matches!(self, Schema::Any | Schema::Optional(_))
+    }
+
+    fn check(
+        &self,
+        value: &JSValueRef,
+        path: &mut String,
+        violations: &mut Vec<String>,
+    ) -> Result<()> {
+        let expected = match self {
+            Schema::Any => return Ok(()),
+            Schema::Optional(_) if value.is_null_or_undefined() => return Ok(()),
+            Schema::Optional(schema) => return schema.check(value, path, violations),
+            Schema::Null if value.is_null() => return Ok(()),
+            Schema::Boolean if value.is_bool() => return Ok(()),
+            Schema::Number if value.is_number() => return Ok(()),
+            Schema::Integer if value.is_integer() => return Ok(()),
+            Schema::String if value.is_str() => return Ok(()),
+            Schema::Array(element) if value.is_array() => {
+                let length = value.get_property("length")?.as_u32_unchecked();
+                for index in 0..length {
+                    let len = path.len();
+                    path.push_str(&format!("[{index}]"));
+                    element.check(&value.get_indexed_property(index)?, path, violations)?;
+                    path.truncate(len);
+                }
+                return Ok(());
+            }
+            Schema::Object {
+                fields,
+                allow_unknown_fields,
+            } if value.is_object() && !value.is_array() && !value.is_function() => {
+                for (name, schema) in fields {
+                    let len = push_field(path, name);
+                    let field = value.get_property(name.as_str())?;
+                    if field.is_undefined() && !schema.accepts_undefined() {
+                        violations.push(format!("`{path}`: missing field"));
+                    } else {
+                        schema.check(&field, path, violations)?;
+                    }
+                    path.truncate(len);
+                }
+
+                if !allow_unknown_fields {
+                    let mut properties = value.properties()?;
+                    while let Some(key) = properties.next_key()? {
+                        let Ok(key) = key.as_str() else { continue };
+                        if !fields.iter().any(|(name, _)| name == key) {
+                            let len = push_field(path, key);
+                            violations.push(format!("`{path}`: unexpected field"));
+                            path.truncate(len);
+                        }
+                    }
+                }
+                return Ok(());
+            }
+            Schema::Null => "null",
+            Schema::Boolean => "a boolean",
+            Schema::Number => "a number",
+            Schema::Integer => "an integer",
+            Schema::String => "a string",
+            Schema::Array(_) => "an array",
+            Schema::Object { .. } => "an object",
+        };
+
+        let at = if path.is_empty() {
+            "the root"
+        } else {
+            path.as_str()
+        };
+        violations.push(format!(
+            "`{at}`: expected {expected}, found {}",
+            type_name(value)
+        ));
+        Ok(())
+    }
+}
+
+/// Appends `name` to `path`, returning the length of `path` before.
+fn push_field(path: &mut String, name: &str) -> usize {
+    let len = path.len();
+    if !path.is_empty() {
+        path.push('.');
+    }
+    path.push_str(name);
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Schema;
+    use crate::js_binding::context::JSContextRef;
+    use crate::serialize::de::Deserializer as ValueDeserializer;
+    use anyhow::Result;
+    use serde::Deserialize;
+
+    fn user_schema() -> Schema {
+        Schema::object([
+            ("name", Schema::String),
+            ("age", Schema::Optional(Box::new(Schema::Integer))),
+            (
+                "pets",
+                Schema::Array(Box::new(Schema::object([
+                    ("name", Schema::String),
+                    ("legs", Schema::Number),
+                ]))),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_conforming_values() -> Result<()> {
+        let context = JSContextRef::default();
+        for source in [
+            "({name: 'javy', pets: []})",
+            "({name: 'javy', age: 3, pets: [{name: 'rex', legs: 4}]})",
+            "({name: 'javy', age: null, pets: [{name: 'rex', legs: 4.5}]})",
+        ] {
+            let val = context.eval_global("main", source)?;
+            user_schema().validate(&val)?;
+        }
+
+        let lenient = Schema::Object {
+            fields: vec![("name".to_string(), Schema::String)],
+            allow_unknown_fields: true,
+        };
+        let val = context.eval_global("main", "({name: 'javy', extra: [1]})")?;
+        lenient.validate(&val)?;
+        Schema::Any.validate(&context.undefined_value()?)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_violations_are_reported_with_their_path() -> Result<()> {
+        let context = JSContextRef::default();
+        let val = context.eval_global(
+            "main",
+            "({age: 3.5, pets: [{name: 'rex', legs: 4}, {name: 7, legs: 4, color: 'red'}], admin: true})",
+        )?;
+        let err = user_schema().validate(&val).unwrap_err();
+        assert_eq!(
+            "value does not match the schema:\n\
+             `name`: missing field\n\
+             `age`: expected an integer, found number\n\
+             `pets[1].name`: expected a string, found number\n\
+             `pets[1].color`: unexpected field\n\
+             `admin`: unexpected field",
+            err.to_string()
+        );
+
+        let err = user_schema()
+            .validate(&context.eval_global("main", "[1]")?)
+            .unwrap_err();
+        assert_eq!(
+            "value does not match the schema:\n`the root`: expected an object, found array",
+            err.to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserializer_validates_before_deserializing() -> Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Item {
+            id: u32,
+        }
+
+        let context = JSContextRef::default();
+        let schema = Schema::Array(Box::new(Schema::object([("id", Schema::Integer)])));
+
+        let val = context.eval_global("main", "[{id: 1}, {id: 2}]")?;
+        let mut deserializer = ValueDeserializer::from(val);
+        let items = Vec::<Item>::deserialize(deserializer.validate(&schema)?)?;
+        assert_eq!(vec![Item { id: 1 }, Item { id: 2 }], items);
+
+        let val = context.eval_global("main", "[{id: 1}, {id: '2'}]")?;
+        let mut deserializer = ValueDeserializer::from(val);
+        let err = deserializer.validate(&schema).err().unwrap();
+        assert!(err
+            .to_string()
+            .ends_with("`[1].id`: expected an integer, found string"));
+        Ok(())
+    }
+}
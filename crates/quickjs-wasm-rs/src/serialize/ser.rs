@@ -27,6 +27,38 @@ pub struct Serializer<'c> {
     pub context: &'c JSContextRef,
     pub value: JSValueRef<'c>,
     pub key: JSValueRef<'c>,
+    options: Options,
+    /// Index of the next element of the sequence being serialized, so the array's length doesn't
+    /// have to be looked up for every element.
+    next_index: u32,
+    /// Existing object that the next map or struct is serialized into.
+    merge_target: Option<JSValueRef<'c>>,
+    /// How keys already present on `value` are handled while serializing into an existing object.
+    on_collision: Option<OnCollision>,
+    /// The `set` method of the JavaScript `Map` being serialized, with [`Serializer::js_maps`].
+    map_set: Option<JSValueRef<'c>>,
+}
+
+/// How [`Serializer::serialize_into`] handles keys that already exist on the target object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnCollision {
+    /// Replace the existing value.
+    Overwrite,
+    /// Fail with an error naming the key.
+    Error,
+}
+
+/// Name of the newtype struct that wraps milliseconds since the Unix epoch to serialize as a
+/// `Date`, see [`crate::adapters::date_time`].
+pub(crate) const DATE_TOKEN: &str = "$quickjs_wasm_rs::Date";
+
+/// Options shared by a serializer and the serializers it creates for nested values.
+#[derive(Clone, Copy, Default)]
+struct Options {
+    unit_as_undefined: bool,
+    precise_f32: bool,
+    enum_as_index: bool,
+    js_maps: bool,
 }
 
 impl SerError for Error {
@@ -41,8 +73,137 @@ impl<'c> Serializer<'c> {
             context,
             value: context.undefined_value()?,
             key: context.undefined_value()?,
+            options: Options::default(),
+            next_index: 0,
+            merge_target: None,
+            on_collision: None,
+            map_set: None,
         })
     }
+
+    /// Serializes `()` and unit structs as `undefined` instead of `null`.
+    ///
+    /// This is disabled by default since JSON has no `undefined`. `None` is always serialized as
+    /// `null`.
+    pub fn unit_as_undefined(&mut self, enabled: bool) -> &mut Self {
+        self.options.unit_as_undefined = enabled;
+        self
+    }
+
+    /// Serializes `f32` values as the number closest to their shortest decimal representation,
+    /// so `1.1f32` becomes `1.1` in JavaScript instead of `1.100000023841858`.
+    ///
+    /// This is disabled by default, which widens `f32` values to `f64` without changing their
+    /// numeric value.
+    pub fn precise_f32(&mut self, enabled: bool) -> &mut Self {
+        self.options.precise_f32 = enabled;
+        self
+    }
+
+    /// Serializes unit variants of enums as their index, in declaration order, instead of their
+    /// name.
+    ///
+    /// This makes the output more compact, but reordering the variants changes what they
+    /// serialize as. [`Deserializer`](crate::Deserializer) accepts both forms. Other variants
+    /// are still objects with the variant's name as their key. Disabled by default.
+    pub fn enum_as_index(&mut self, enabled: bool) -> &mut Self {
+        self.options.enum_as_index = enabled;
+        self
+    }
+
+    /// Serializes maps as JavaScript `Map`s instead of plain objects.
+    ///
+    /// Objects list integer-like keys first, in ascending order, and other keys in insertion
+    /// order, so a map whose keys mix both can't keep its order as an object. A `Map` always keeps
+    /// the order entries were serialized in, and its keys keep their type, so numeric keys stay
+    /// numbers. [`Deserializer`](crate::Deserializer) reads the entries of `Map`s, and reads their
+    /// string keys like object keys, so options transforming strings don't apply to them.
+    ///
+    /// Structs are still serialized as objects, except for structs with `#[serde(flatten)]`
+    /// fields, which serde serializes as maps so they become `Map`s too. Disabled by default.
+    pub fn js_maps(&mut self, enabled: bool) -> &mut Self {
+        self.options.js_maps = enabled;
+        self
+    }
+
+    /// Adds `element` after the ones added since `serialize_seq` or `serialize_tuple_variant`.
+    fn push_element(&mut self, element: JSValueRef<'c>) -> Result<()> {
+        self.value.set_indexed_property(self.next_index, element)?;
+        self.next_index += 1;
+        Ok(())
+    }
+
+    /// Creates a serializer for a nested value that uses the same options.
+    fn nested(&self) -> Result<Self> {
+        let mut serializer = Self::from_context(self.context)?;
+        serializer.options = self.options;
+        Ok(serializer)
+    }
+
+    /// Serializes the fields of `data` onto the existing `target` object instead of a new one.
+    ///
+    /// `data` must serialize as a map or a struct. Keys of `data` that already exist on `target`
+    /// are handled according to `on_collision`. On success, the serializer's value is `target`.
+    /// Fields are set one at a time, so fields preceding a rejected key have already been set
+    /// when an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let target = context.eval_global("main", "({id: 1})")?;
+    /// let mut serializer = Serializer::from_context(&context)?;
+    /// serializer.serialize_into(target, &host_data, OnCollision::Error)?;
+    /// ```
+    pub fn serialize_into<T>(
+        &mut self,
+        target: JSValueRef<'c>,
+        data: &T,
+        on_collision: OnCollision,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        if !target.is_object() {
+            return Err(Error::Custom(anyhow!(
+                "Can only serialize into an object, found {target:?}"
+            )));
+        }
+
+        self.merge_target = Some(target);
+        self.on_collision = Some(on_collision);
+        let result = data.serialize(&mut *self);
+        self.on_collision = None;
+        let unused_target = self.merge_target.take();
+        result?;
+
+        if unused_target.is_some() {
+            return Err(Error::Custom(anyhow!(
+                "Only maps and structs can be serialized into an existing object"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Starts serializing a map or struct as an object, or into the target of
+    /// [`Serializer::serialize_into`].
+    fn start_object(&mut self) -> Result<()> {
+        self.value = match self.merge_target.take() {
+            Some(target) => target,
+            None => self.context.object_value()?,
+        };
+        Ok(())
+    }
+
+    /// Sets the property `key` of the object being serialized.
+    fn set_field(&self, key: &str, value: JSValueRef) -> Result<()> {
+        if self.on_collision == Some(OnCollision::Error) && self.value.has_own_property(key)? {
+            return Err(Error::Custom(anyhow!(
+                "Key `{key}` already exists on the target object"
+            )));
+        }
+        self.value.set_property(key, value)?;
+        Ok(())
+    }
 }
 
 impl<'a> ser::Serializer for &'a mut Serializer<'_> {
@@ -94,6 +255,11 @@ impl<'a> ser::Serializer for &'a mut Serializer<'_> {
     }
 
     fn serialize_f32(self, v: f32) -> Result<()> {
+        if self.options.precise_f32 && v.is_finite() {
+            // `Display` prints the shortest decimal that round-trips to the same `f32`.
+            let v = v.to_string().parse::<f64>().map_err(anyhow::Error::new)?;
+            return self.serialize_f64(v);
+        }
         // NOTE: See optimization note in serialize_f64.
         self.serialize_f64(f64::from(v))
     }
@@ -111,7 +277,9 @@ impl<'a> ser::Serializer for &'a mut Serializer<'_> {
     }
 
     fn serialize_char(self, v: char) -> Result<()> {
-        self.serialize_str(&v.to_string())
+        // QuickJS converts the UTF-8 encoding to UTF-16, so characters outside of the Basic
+        // Multilingual Plane become a surrogate pair like in JavaScript.
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
     }
 
     fn serialize_str(self, v: &str) -> Result<()> {
@@ -120,11 +288,16 @@ impl<'a> ser::Serializer for &'a mut Serializer<'_> {
     }
 
     fn serialize_none(self) -> Result<()> {
-        self.serialize_unit()
+        self.value = self.context.null_value()?;
+        Ok(())
     }
 
     fn serialize_unit(self) -> Result<()> {
-        self.value = self.context.null_value()?;
+        self.value = if self.options.unit_as_undefined {
+            self.context.undefined_value()?
+        } else {
+            self.context.null_value()?
+        };
         Ok(())
     }
 
@@ -142,21 +315,32 @@ impl<'a> ser::Serializer for &'a mut Serializer<'_> {
     fn serialize_unit_variant(
         self,
         _name: &'static str,
-        _variant_index: u32,
+        variant_index: u32,
         variant: &'static str,
     ) -> Result<()> {
-        self.serialize_str(variant)
+        if self.options.enum_as_index {
+            self.value = self.context.value_from_u32(variant_index)?;
+            Ok(())
+        } else {
+            self.serialize_str(variant)
+        }
     }
 
-    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(self)
+        value.serialize(&mut *self)?;
+        if name == DATE_TOKEN {
+            let millis = self.value.as_f64()?;
+            self.value = self.context.date_from_epoch_millis(millis as i64)?;
+        }
+        Ok(())
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
         self.value = self.context.array_value()?;
+        self.next_index = 0;
         Ok(self)
     }
 
@@ -173,12 +357,18 @@ impl<'a> ser::Serializer for &'a mut Serializer<'_> {
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-        self.value = self.context.object_value()?;
+        if self.options.js_maps && self.merge_target.is_none() {
+            self.value = self.context.new_map()?;
+            self.map_set = Some(self.value.get_property("set")?);
+        } else {
+            self.start_object()?;
+        }
         Ok(self)
     }
 
-    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
-        self.serialize_map(Some(len))
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        self.start_object()?;
+        Ok(self)
     }
 
     fn serialize_struct_variant(
@@ -186,9 +376,10 @@ impl<'a> ser::Serializer for &'a mut Serializer<'_> {
         _name: &'static str,
         _variant_index: u32,
         _variant: &'static str,
-        len: usize,
+        _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        self.serialize_map(Some(len))
+        self.start_object()?;
+        Ok(self)
     }
 
     fn serialize_tuple_variant(
@@ -196,9 +387,11 @@ impl<'a> ser::Serializer for &'a mut Serializer<'_> {
         _name: &'static str,
         _variant_index: u32,
         _variant: &'static str,
-        len: usize,
+        _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        self.serialize_map(Some(len))
+        self.start_object()?;
+        self.next_index = 0;
+        Ok(self)
     }
 
     fn serialize_newtype_variant<T>(
@@ -234,10 +427,9 @@ impl<'a> ser::SerializeSeq for &'a mut Serializer<'_> {
     where
         T: ?Sized + Serialize,
     {
-        let mut element_serializer = Serializer::from_context(self.context)?;
+        let mut element_serializer = self.nested()?;
         value.serialize(&mut element_serializer)?;
-        self.value.append_property(element_serializer.value)?;
-        Ok(())
+        self.push_element(element_serializer.value)
     }
 
     fn end(self) -> Result<()> {
@@ -253,10 +445,9 @@ impl<'a> ser::SerializeTuple for &'a mut Serializer<'_> {
     where
         T: ?Sized + Serialize,
     {
-        let mut element_serializer = Serializer::from_context(self.context)?;
+        let mut element_serializer = self.nested()?;
         value.serialize(&mut element_serializer)?;
-        self.value.append_property(element_serializer.value)?;
-        Ok(())
+        self.push_element(element_serializer.value)
     }
 
     fn end(self) -> Result<()> {
@@ -272,10 +463,9 @@ impl<'a> ser::SerializeTupleStruct for &'a mut Serializer<'_> {
     where
         T: ?Sized + Serialize,
     {
-        let mut field_serializer = Serializer::from_context(self.context)?;
+        let mut field_serializer = self.nested()?;
         value.serialize(&mut field_serializer)?;
-        self.value.append_property(field_serializer.value)?;
-        Ok(())
+        self.push_element(field_serializer.value)
     }
 
     fn end(self) -> Result<()> {
@@ -291,10 +481,9 @@ impl<'a> ser::SerializeTupleVariant for &'a mut Serializer<'_> {
     where
         T: ?Sized + Serialize,
     {
-        let mut field_serializer = Serializer::from_context(self.context)?;
+        let mut field_serializer = self.nested()?;
         value.serialize(&mut field_serializer)?;
-        self.value.append_property(field_serializer.value)?;
-        Ok(())
+        self.push_element(field_serializer.value)
     }
 
     fn end(self) -> Result<()> {
@@ -310,7 +499,7 @@ impl<'a> ser::SerializeMap for &'a mut Serializer<'_> {
     where
         T: ?Sized + Serialize,
     {
-        let mut key_serializer = Serializer::from_context(self.context)?;
+        let mut key_serializer = self.nested()?;
         key.serialize(&mut key_serializer)?;
         self.key = key_serializer.value;
         Ok(())
@@ -320,11 +509,14 @@ impl<'a> ser::SerializeMap for &'a mut Serializer<'_> {
     where
         T: ?Sized + Serialize,
     {
-        let mut map_serializer = Serializer::from_context(self.context)?;
+        let mut map_serializer = self.nested()?;
         value.serialize(&mut map_serializer)?;
+        if let Some(set) = self.map_set {
+            set.call(&self.value, &[self.key, map_serializer.value])?;
+            return Ok(());
+        }
         let key = as_key(&self.key)?;
-        self.value.set_property(key, map_serializer.value)?;
-        Ok(())
+        self.set_field(key, map_serializer.value)
     }
 
     fn end(self) -> Result<()> {
@@ -340,10 +532,9 @@ impl<'a> ser::SerializeStruct for &'a mut Serializer<'_> {
     where
         T: ?Sized + Serialize,
     {
-        let mut field_serializer = Serializer::from_context(self.context)?;
+        let mut field_serializer = self.nested()?;
         value.serialize(&mut field_serializer)?;
-        self.value.set_property(key, field_serializer.value)?;
-        Ok(())
+        self.set_field(key, field_serializer.value)
     }
 
     fn end(self) -> Result<()> {
@@ -359,7 +550,7 @@ impl<'a> ser::SerializeStructVariant for &'a mut Serializer<'_> {
     where
         T: ?Sized + Serialize,
     {
-        let mut field_serializer = Serializer::from_context(self.context)?;
+        let mut field_serializer = self.nested()?;
         value.serialize(&mut field_serializer)?;
         self.value.set_property(key, field_serializer.value)?;
         Ok(())
@@ -374,7 +565,7 @@ impl<'a> ser::SerializeStructVariant for &'a mut Serializer<'_> {
 mod tests {
     use std::collections::BTreeMap;
 
-    use super::Serializer as ValueSerializer;
+    use super::{OnCollision, Serializer as ValueSerializer};
     use crate::js_binding::{
         constants::{MAX_SAFE_INTEGER, MIN_SAFE_INTEGER},
         context::JSContextRef,
@@ -528,6 +719,67 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_unit_as_undefined() -> Result<()> {
+        #[derive(serde::Serialize)]
+        struct Marker;
+
+        #[derive(serde::Serialize)]
+        struct MyObject {
+            marker: Marker,
+            unit: (),
+            missing: Option<u32>,
+        }
+
+        let my_object = MyObject {
+            marker: Marker,
+            unit: (),
+            missing: None,
+        };
+
+        let context = JSContextRef::default();
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        my_object.serialize(&mut serializer)?;
+        assert!(serializer.value.get_property("marker")?.is_null());
+        assert!(serializer.value.get_property("unit")?.is_null());
+        assert!(serializer.value.get_property("missing")?.is_null());
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.unit_as_undefined(true);
+        my_object.serialize(&mut serializer)?;
+        assert!(serializer.value.get_property("marker")?.is_undefined());
+        assert!(serializer.value.get_property("unit")?.is_undefined());
+        assert!(serializer.value.get_property("missing")?.is_null());
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.unit_as_undefined(true).serialize_unit()?;
+        assert!(serializer.value.is_undefined());
+        Ok(())
+    }
+
+    #[test]
+    fn test_precise_f32() -> Result<()> {
+        let context = JSContextRef::default();
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.serialize_f32(1.1)?;
+        assert_eq!(f64::from(1.1f32), serializer.value.as_f64()?);
+        assert_ne!(1.1, serializer.value.as_f64()?);
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.precise_f32(true).serialize_f32(1.1)?;
+        assert_eq!(1.1, serializer.value.as_f64()?);
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.precise_f32(true);
+        vec![0.1f32, f32::INFINITY].serialize(&mut serializer)?;
+        assert_eq!(0.1, serializer.value.get_indexed_property(0)?.as_f64()?);
+        assert_eq!(
+            f64::INFINITY,
+            serializer.value.get_indexed_property(1)?.as_f64()?
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_nan() -> Result<()> {
         let context = JSContextRef::default();
@@ -620,6 +872,40 @@ mod tests {
         assert!(serializer.value.is_array());
     }
 
+    #[test]
+    fn test_sequence_of_unknown_length() -> Result<()> {
+        let context = JSContextRef::default();
+        let mut serializer = ValueSerializer::from_context(&context)?;
+
+        // `filter` doesn't know its exact length, so `collect_seq` passes `None` as the length.
+        let odd = (0..10).filter(|n| n % 2 == 1);
+        serializer.collect_seq(odd.map(|n| (0..n).filter(|m| m % 2 == 0).collect::<Vec<_>>()))?;
+
+        let value = serializer.value;
+        assert!(value.is_array());
+        assert_eq!(5, value.get_property("length")?.as_u32_unchecked());
+        let last = value.get_indexed_property(4)?;
+        assert_eq!("0,2,4,6,8", last.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tuple_variant() -> Result<()> {
+        #[derive(Serialize)]
+        enum Shape {
+            Line(u32, u32),
+        }
+
+        let context = JSContextRef::default();
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        Shape::Line(3, 4).serialize(&mut serializer)?;
+
+        let value = serializer.value;
+        assert_eq!(3, value.get_property("0")?.as_u32_unchecked());
+        assert_eq!(4, value.get_property("1")?.as_u32_unchecked());
+        Ok(())
+    }
+
     #[test]
     fn test_array_buffer() {
         let context = JSContextRef::default();
@@ -633,4 +919,80 @@ mod tests {
 
         assert_eq!(serializer.value.as_bytes().unwrap(), &[42u8, 0, 255]);
     }
+
+    #[derive(Serialize)]
+    struct HostData {
+        user: String,
+        count: u32,
+    }
+
+    #[test]
+    fn test_serialize_into_existing_object() -> Result<()> {
+        let context = JSContextRef::default();
+        let target = context.eval_global("main", "({id: 7, count: 1})")?;
+        let data = HostData {
+            user: "javy".to_string(),
+            count: 2,
+        };
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.serialize_into(target, &data, OnCollision::Overwrite)?;
+
+        let value = serializer.value;
+        assert_eq!(7, value.get_property("id")?.as_i32_unchecked());
+        assert_eq!("javy", value.get_property("user")?.as_str()?);
+        assert_eq!(2, value.get_property("count")?.as_i32_unchecked());
+        assert_eq!(
+            "javy",
+            target.get_property("user")?.as_str()?,
+            "the target object itself is updated"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_into_with_collision_error() -> Result<()> {
+        let context = JSContextRef::default();
+        let target = context.eval_global("main", "({id: 7, count: 1})")?;
+        let data = HostData {
+            user: "javy".to_string(),
+            count: 2,
+        };
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        let err = serializer
+            .serialize_into(target, &data, OnCollision::Error)
+            .unwrap_err();
+        assert_eq!(
+            "Key `count` already exists on the target object",
+            err.to_string()
+        );
+        assert_eq!(1, target.get_property("count")?.as_i32_unchecked());
+
+        let mut map = BTreeMap::new();
+        map.insert("name", "javy");
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        serializer.serialize_into(target, &map, OnCollision::Error)?;
+        assert_eq!("javy", target.get_property("name")?.as_str()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_into_requires_maps_and_objects() -> Result<()> {
+        let context = JSContextRef::default();
+        let target = context.eval_global("main", "({})")?;
+
+        let mut serializer = ValueSerializer::from_context(&context)?;
+        assert!(serializer
+            .serialize_into(target, &42, OnCollision::Overwrite)
+            .is_err());
+        assert!(serializer
+            .serialize_into(
+                context.value_from_i32(1)?,
+                &BTreeMap::<String, u32>::new(),
+                OnCollision::Overwrite
+            )
+            .is_err());
+        Ok(())
+    }
 }
@@ -21,6 +21,14 @@ JSValue JS_NewFloat64_Ext(JSContext *ctx, double d) {
   return JS_NewFloat64(ctx, d);
 }
 
+JSValue JS_DupValue_Ext(JSContext *ctx, JSValue val) {
+  return JS_DupValue(ctx, val);
+}
+
+void JS_FreeValue_Ext(JSContext *ctx, JSValue val) {
+  JS_FreeValue(ctx, val);
+}
+
 JS_BOOL JS_IsFloat64_Ext(int tag) {
   return JS_TAG_IS_FLOAT64(tag);
 }
@@ -72,6 +80,11 @@ int JS_BigIntToUint64(JSContext *ctx, uint64_t *pres, JSValueConst val) {
   return JS_BigIntToUint64Free(ctx, pres, JS_DupValue(ctx, val));
 }
 
+// returns the module definition held by a module compiled with JS_EVAL_FLAG_COMPILE_ONLY
+JSModuleDef *JS_GetModuleDef_Ext(JSValue val) {
+  return JS_VALUE_GET_PTR(val);
+}
+
 
 const JSValue ext_js_null = JS_NULL;
 const JSValue ext_js_undefined = JS_UNDEFINED;
@@ -1791,6 +1791,14 @@ void JS_SetInterruptHandler(JSRuntime *rt, JSInterruptHandler *cb, void *opaque)
     rt->interrupt_opaque = opaque;
 }
 
+/* quickjs-wasm-sys extension: return the handler set with
+   JS_SetInterruptHandler() along with its opaque pointer */
+JSInterruptHandler *JS_GetInterruptHandler_Ext(JSRuntime *rt, void **popaque)
+{
+    *popaque = rt->interrupt_opaque;
+    return rt->interrupt_handler;
+}
+
 void JS_SetCanBlock(JSRuntime *rt, BOOL can_block)
 {
     rt->can_block = can_block;
@@ -15555,6 +15563,13 @@ static BOOL js_get_fast_array(JSContext *ctx, JSValueConst obj,
     return FALSE;
 }
 
+/* quickjs-wasm-sys extension: exported js_get_fast_array() */
+JS_BOOL JS_GetFastArray_Ext(JSContext *ctx, JSValueConst obj,
+                            JSValue **arrpp, uint32_t *countp)
+{
+    return js_get_fast_array(ctx, obj, arrpp, countp);
+}
+
 static __exception int js_append_enumerate(JSContext *ctx, JSValue *sp)
 {
     JSValue iterator, enumobj, method, value;
@@ -46336,6 +46351,15 @@ void JS_SetHostPromiseRejectionTracker(JSRuntime *rt,
     rt->host_promise_rejection_tracker_opaque = opaque;
 }
 
+/* quickjs-wasm-sys extension: return the tracker set with
+   JS_SetHostPromiseRejectionTracker() along with its opaque pointer */
+JSHostPromiseRejectionTracker *JS_GetHostPromiseRejectionTracker_Ext(JSRuntime *rt,
+                                                                     void **popaque)
+{
+    *popaque = rt->host_promise_rejection_tracker_opaque;
+    return rt->host_promise_rejection_tracker;
+}
+
 static void fulfill_or_reject_promise(JSContext *ctx, JSValueConst promise,
                                       JSValueConst value, BOOL is_reject)
 {